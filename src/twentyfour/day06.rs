@@ -7,119 +7,16 @@ use crate::create_advent_day;
 create_advent_day!("2024", "06");
 
 fn part1_with_input(input: &str) -> i64 {
-    let mut guard = Guard {
-        x_position: 0,
-        y_position: 0,
-        direction: Direction::NORTH,
-    };
-    let mut map: Vec<Vec<Cell>> = input
-        .lines()
-        .enumerate()
-        .map(|(line_num, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(char_num, letter)| {
-                    let cell_type = if letter == '#' {
-                        CellType::OBSTACLE
-                    } else {
-                        CellType::EMPTY
-                    };
-                    let visited = if letter == '^' {
-                        guard.x_position = char_num as i32;
-                        guard.y_position = line_num as i32;
-                        true
-                    } else {
-                        false
-                    };
-                    Cell { cell_type, visited }
-                })
-                .collect::<Vec<Cell>>()
-        })
-        .collect::<Vec<Vec<Cell>>>();
-
-    while guard.patrol(&mut map) {}
-
-    return map
-        .iter()
-        .map(|row| row.iter().filter(|cell| cell.visited).count() as i64)
-        .sum();
-}
-
-#[derive(Clone)]
-struct Cell {
-    cell_type: CellType,
-    visited: bool,
-}
+    let lab = Lab::parse(input);
+    let (visited, _) = lab.patrol();
 
-#[derive(PartialEq, Clone)]
-enum CellType {
-    EMPTY,
-    OBSTACLE,
+    return visited.iter().filter(|cell| **cell).count() as i64;
 }
 
-struct Guard {
-    x_position: i32,
-    y_position: i32,
-    direction: Direction,
-}
-
-impl Guard {
-    fn next_position(&self) -> (i32, i32) {
-        return match self.direction {
-            Direction::NORTH => (self.x_position, self.y_position - 1),
-            Direction::EAST => (self.x_position + 1, self.y_position),
-            Direction::SOUTH => (self.x_position, self.y_position + 1),
-            Direction::WEST => (self.x_position - 1, self.y_position),
-        };
-    }
-
-    fn patrol(&mut self, map: &mut Vec<Vec<Cell>>) -> bool {
-        let (x, y) = self.next_position();
-        if x < 0 || x >= map[0].len() as i32 || y < 0 || y >= map.len() as i32 {
-            return false;
-        }
-        let next_cell = &mut map[y as usize][x as usize];
-        if next_cell.cell_type == CellType::OBSTACLE {
-            self.direction = self.direction.rotate();
-        } else {
-            self.x_position = x;
-            self.y_position = y;
-            next_cell.visited = true;
-        }
-        return true;
-    }
-
-    fn patrol_with_cache(
-        &mut self,
-        map: &Vec<Vec<Cell>>,
-        cache: &mut HashSet<(i32, i32, Direction)>,
-    ) -> PatrolOutcome {
-        let (x, y) = self.next_position();
-        if x < 0 || x >= map[0].len() as i32 || y < 0 || y >= map.len() as i32 {
-            return PatrolOutcome::EXIT;
-        }
-        let next_cell = map.get(y as usize).unwrap().get(x as usize).unwrap();
-        if next_cell.cell_type == CellType::OBSTACLE {
-            self.direction = self.direction.rotate();
-        } else {
-            self.x_position = x;
-            self.y_position = y;
-        }
-        let tuple = (self.x_position, self.y_position, self.direction);
-        if cache.contains(&tuple) {
-            return PatrolOutcome::INFINITE;
-        } else {
-            cache.insert(tuple);
-            return PatrolOutcome::CONTINUE;
-        }
-    }
-}
+fn part2_with_input(input: &str) -> i64 {
+    let lab = Lab::parse(input);
 
-#[derive(PartialEq)]
-enum PatrolOutcome {
-    CONTINUE,
-    EXIT,
-    INFINITE,
+    return lab.loop_positions().len() as i64;
 }
 
 #[derive(Eq, Hash, PartialEq, Clone, Copy)]
@@ -139,91 +36,202 @@ impl Direction {
             Direction::WEST => Direction::NORTH,
         };
     }
+
+    fn index(&self) -> usize {
+        return match self {
+            Direction::NORTH => 0,
+            Direction::EAST => 1,
+            Direction::SOUTH => 2,
+            Direction::WEST => 3,
+        };
+    }
+
+    fn offset(&self) -> (i32, i32) {
+        return match self {
+            Direction::NORTH => (0, -1),
+            Direction::EAST => (1, 0),
+            Direction::SOUTH => (0, 1),
+            Direction::WEST => (-1, 0),
+        };
+    }
 }
 
-fn part2_with_input(input: &str) -> i64 {
-    let mut guard = Guard {
-        x_position: 0,
-        y_position: 0,
-        direction: Direction::NORTH,
-    };
-    let mut map: Vec<Vec<Cell>> = input
-        .lines()
-        .enumerate()
-        .map(|(line_num, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(char_num, letter)| {
-                    let cell_type = if letter == '#' {
-                        CellType::OBSTACLE
-                    } else {
-                        CellType::EMPTY
-                    };
-                    let visited = if letter == '^' {
-                        guard.x_position = char_num as i32;
-                        guard.y_position = line_num as i32;
-                        true
-                    } else {
-                        false
-                    };
-                    Cell { cell_type, visited }
-                })
-                .collect::<Vec<Cell>>()
-        })
-        .collect::<Vec<Vec<Cell>>>();
-
-    let original_position = (guard.x_position as usize, guard.y_position as usize);
-    while guard.patrol(&mut map) {}
-    let visited_cells: Vec<(i64, i64)> = map
-        .iter()
-        .enumerate()
-        .map(|(row_num, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(|(cell_num, cell)| {
-                    if cell.visited {
-                        if cell_num == original_position.0 && row_num == original_position.1 {
-                            Err(0)
-                        } else {
-                            Ok((cell_num as i64, row_num as i64))
-                        }
-                    } else {
-                        Err(0)
-                    }
-                    .ok()
-                })
-                .collect::<Vec<(i64, i64)>>()
-        })
-        .flatten()
-        .collect();
-
-    return visited_cells
-        .par_iter()
-        .map(|(x_int, y_int)| {
-            let x_size = *x_int as usize;
-            let y_size = *y_int as usize;
-            let mut my_map = map.clone();
-            my_map[y_size][x_size].cell_type = CellType::OBSTACLE;
-            let mut my_guard = Guard {
-                x_position: original_position.0 as i32,
-                y_position: original_position.1 as i32,
-                direction: Direction::NORTH,
-            };
-            let mut outcome: PatrolOutcome;
-            let mut cache = HashSet::new();
-            loop {
-                outcome = my_guard.patrol_with_cache(&my_map, &mut cache);
-                if outcome != PatrolOutcome::CONTINUE {
-                    break;
+const DIRECTIONS: [Direction; 4] = [
+    Direction::NORTH,
+    Direction::EAST,
+    Direction::SOUTH,
+    Direction::WEST,
+];
+
+// A spot where a new obstacle could go, along with where the guard is standing and facing the
+// first time they would walk into it.
+struct Candidate {
+    obstacle: (usize, usize),
+    guard: (usize, usize),
+    direction: Direction,
+}
+
+struct Lab {
+    width: usize,
+    height: usize,
+    obstacles: Vec<bool>,
+    start: (usize, usize),
+    // For every cell and direction, the cell the guard stops on before the next obstacle,
+    // or None if they walk out of the lab.
+    jumps: Vec<[Option<(usize, usize)>; 4]>,
+}
+
+impl Lab {
+    fn parse(input: &str) -> Lab {
+        let mut start = (0, 0);
+        let mut obstacles = Vec::new();
+        let mut height = 0;
+        for (line_num, line) in input.lines().enumerate() {
+            for (char_num, letter) in line.chars().enumerate() {
+                if letter == '^' {
+                    start = (char_num, line_num);
                 }
+                obstacles.push(letter == '#');
+            }
+            height += 1;
+        }
+        let width = obstacles.len() / height;
+
+        let mut lab = Lab {
+            width,
+            height,
+            obstacles,
+            start,
+            jumps: vec![[None; 4]; width * height],
+        };
+        for direction in DIRECTIONS {
+            lab.fill_jumps(direction);
+        }
+        return lab;
+    }
+
+    fn fill_jumps(&mut self, direction: Direction) {
+        let (dx, dy) = direction.offset();
+        // Visit cells so that the one ahead of the guard is always filled in first.
+        let xs: Vec<usize> = if dx > 0 {
+            (0..self.width).rev().collect()
+        } else {
+            (0..self.width).collect()
+        };
+        let ys: Vec<usize> = if dy > 0 {
+            (0..self.height).rev().collect()
+        } else {
+            (0..self.height).collect()
+        };
+        for y in ys.iter() {
+            for x in xs.iter() {
+                let jump = match self.step((*x, *y), direction) {
+                    None => None,
+                    Some(ahead) if self.is_obstacle(ahead) => Some((*x, *y)),
+                    Some(ahead) => self.jump(ahead, direction),
+                };
+                self.jumps[y * self.width + x][direction.index()] = jump;
+            }
+        }
+    }
+
+    fn step(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.offset();
+        let x = position.0 as i32 + dx;
+        let y = position.1 as i32 + dy;
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return None;
+        }
+        return Some((x as usize, y as usize));
+    }
+
+    fn jump(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        return self.jumps[position.1 * self.width + position.0][direction.index()];
+    }
+
+    fn is_obstacle(&self, position: (usize, usize)) -> bool {
+        return self.obstacles[position.1 * self.width + position.0];
+    }
+
+    // Walks the original patrol one cell at a time, returning every visited cell and the
+    // candidate obstacle spots in the order the guard first reaches them.
+    fn patrol(&self) -> (Vec<bool>, Vec<Candidate>) {
+        let mut visited = vec![false; self.width * self.height];
+        let mut candidates = Vec::new();
+        let mut guard = self.start;
+        let mut direction = Direction::NORTH;
+        visited[guard.1 * self.width + guard.0] = true;
+        while let Some(ahead) = self.step(guard, direction) {
+            if self.is_obstacle(ahead) {
+                direction = direction.rotate();
+                continue;
+            }
+            let cell = &mut visited[ahead.1 * self.width + ahead.0];
+            if !*cell {
+                *cell = true;
+                candidates.push(Candidate {
+                    obstacle: ahead,
+                    guard,
+                    direction,
+                });
             }
-            if outcome == PatrolOutcome::INFINITE {
-                1
-            } else {
-                0
+            guard = ahead;
+        }
+        return (visited, candidates);
+    }
+
+    fn loop_positions(&self) -> Vec<(usize, usize)> {
+        let (_, candidates) = self.patrol();
+        return candidates
+            .par_iter()
+            .filter(|candidate| self.loops_with(candidate))
+            .map(|candidate| candidate.obstacle)
+            .collect();
+    }
+
+    // Resumes the patrol from just in front of the new obstacle, jumping from turn to turn.
+    // The extra obstacle is an overlay, so it only needs checking against each jump's segment.
+    fn loops_with(&self, candidate: &Candidate) -> bool {
+        let obstacle = candidate.obstacle;
+        let mut guard = candidate.guard;
+        let mut direction = candidate.direction.rotate();
+        let mut turns = HashSet::new();
+        loop {
+            if !turns.insert((guard, direction)) {
+                return true;
             }
-        })
-        .sum();
+            let jump = self.jump(guard, direction);
+            let blocked_at =
+                distance_ahead(guard, direction, obstacle).filter(|distance| match jump {
+                    Some(stop) => *distance <= distance_ahead(guard, direction, stop).unwrap_or(0),
+                    None => true,
+                });
+            guard = match (blocked_at, jump) {
+                (Some(distance), _) => {
+                    let (dx, dy) = direction.offset();
+                    let steps = distance as i32 - 1;
+                    (
+                        (guard.0 as i32 + dx * steps) as usize,
+                        (guard.1 as i32 + dy * steps) as usize,
+                    )
+                }
+                (None, Some(stop)) => stop,
+                (None, None) => return false,
+            };
+            direction = direction.rotate();
+        }
+    }
+}
+
+fn distance_ahead(from: (usize, usize), direction: Direction, to: (usize, usize)) -> Option<usize> {
+    let distance = match direction {
+        Direction::NORTH if from.0 == to.0 && to.1 < from.1 => from.1 - to.1,
+        Direction::SOUTH if from.0 == to.0 && to.1 > from.1 => to.1 - from.1,
+        Direction::WEST if from.1 == to.1 && to.0 < from.0 => from.0 - to.0,
+        Direction::EAST if from.1 == to.1 && to.0 > from.0 => to.0 - from.0,
+        _ => return None,
+    };
+    return Some(distance);
 }
 
 #[cfg(test)]
@@ -243,4 +251,23 @@ mod tests {
 
         assert_eq!("6", &actual);
     }
+
+    #[test]
+    fn loop_positions_works() {
+        let lab = Lab::parse(&utils::read_file("2024/day06", "test.txt"));
+
+        let mut actual = lab.loop_positions();
+        actual.sort();
+
+        assert_eq!(vec![(1, 8), (3, 6), (3, 8), (6, 7), (7, 7), (7, 9)], actual);
+    }
+
+    #[test]
+    fn jumps_stop_before_obstacles() {
+        let lab = Lab::parse(&utils::read_file("2024/day06", "test.txt"));
+
+        assert_eq!(Some((4, 1)), lab.jump((4, 6), Direction::NORTH));
+        assert_eq!(Some((8, 1)), lab.jump((4, 1), Direction::EAST));
+        assert_eq!(None, lab.jump((4, 6), Direction::SOUTH));
+    }
 }
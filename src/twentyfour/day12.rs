@@ -1,98 +1,20 @@
-use std::{collections::HashMap, usize};
-
 use crate::create_advent_day;
 
 create_advent_day!("2024", "12");
 
 fn part1_with_input(input: &str) -> i64 {
-    let plots: Vec<Vec<Plot>> = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|letter| Plot {
-                    crop: letter,
-                    region: usize::MAX,
-                })
-                .collect()
-        })
-        .collect();
-
-    let height = plots.len();
-    let width = plots[0].len();
-
-    let mut garden = Garden {
-        plots,
-        height,
-        width,
-    };
-
-    let mut regions: HashMap<usize, Region> = HashMap::new();
-    let mut next_region: usize = 0;
-    for x in 0..width {
-        for y in 0..height {
-            if garden.plots[y][x].region != usize::MAX {
-                continue;
-            }
-            regions.insert(
-                next_region,
-                Region {
-                    area: 0,
-                    _crop: garden.plots[y][x].crop,
-                    perimeter: 0,
-                },
-            );
-            define_region(&mut garden, x, y, next_region, &mut regions);
-            next_region += 1;
-        }
-    }
-
-    for x in 0..width {
-        for y in 0..height {
-            if x == 0 || garden.plots[y][x - 1].crop != garden.plots[y][x].crop {
-                regions
-                    .get_mut(&garden.plots[y][x].region)
-                    .unwrap()
-                    .perimeter += 1;
-            }
-            if x == garden.width - 1 || garden.plots[y][x + 1].crop != garden.plots[y][x].crop {
-                regions
-                    .get_mut(&garden.plots[y][x].region)
-                    .unwrap()
-                    .perimeter += 1;
-            }
-            if y == 0 || garden.plots[y - 1][x].crop != garden.plots[y][x].crop {
-                regions
-                    .get_mut(&garden.plots[y][x].region)
-                    .unwrap()
-                    .perimeter += 1;
-            }
-            if y == garden.height - 1 || garden.plots[y + 1][x].crop != garden.plots[y][x].crop {
-                regions
-                    .get_mut(&garden.plots[y][x].region)
-                    .unwrap()
-                    .perimeter += 1;
-            }
-        }
-    }
+    return Garden::parse(input).total_price(Pricing::Perimeter) as i64;
+}
 
-    return regions
-        .values()
-        .map(|region| region.perimeter * region.area)
-        .sum::<usize>() as i64;
+fn part2_with_input(input: &str) -> i64 {
+    return Garden::parse(input).total_price(Pricing::Sides) as i64;
 }
 
-fn define_region(
-    garden: &mut Garden,
-    x: usize,
-    y: usize,
-    region: usize,
-    regions: &mut HashMap<usize, Region>,
-) {
+fn define_region(garden: &mut Garden, x: usize, y: usize, region: usize) {
     if garden.plots[y][x].region != usize::MAX {
         return;
     }
     garden.plots[y][x].region = region;
-    regions.get_mut(&region).unwrap().area += 1;
     let mut neighbors: Vec<(usize, usize)> = Vec::new();
     if x > 0 {
         neighbors.push((x - 1, y));
@@ -114,13 +36,20 @@ fn define_region(
         .collect();
     real_neighbors
         .iter()
-        .for_each(|(x, y)| define_region(garden, *x, *y, region, regions));
+        .for_each(|(x, y)| define_region(garden, *x, *y, region));
+}
+
+#[derive(Clone, Copy)]
+enum Pricing {
+    Perimeter,
+    Sides,
 }
 
 struct Garden {
     plots: Vec<Vec<Plot>>,
     height: usize,
     width: usize,
+    regions: Vec<Region>,
 }
 
 struct Plot {
@@ -132,14 +61,138 @@ struct Region {
     _crop: char,
     perimeter: usize,
     area: usize,
+    sides: usize,
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+impl Garden {
+    fn parse(input: &str) -> Garden {
+        let plots: Vec<Vec<Plot>> = input
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|letter| Plot {
+                        crop: letter,
+                        region: usize::MAX,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let height = plots.len();
+        let width = plots[0].len();
+
+        let mut garden = Garden {
+            plots,
+            height,
+            width,
+            regions: Vec::new(),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                if garden.plots[y][x].region != usize::MAX {
+                    continue;
+                }
+                let next_region = garden.regions.len();
+                garden.regions.push(Region {
+                    area: 0,
+                    _crop: garden.plots[y][x].crop,
+                    perimeter: 0,
+                    sides: 0,
+                });
+                define_region(&mut garden, x, y, next_region);
+            }
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let perimeter = garden.fences(x, y);
+                let sides = garden.corners(x, y);
+                let region = &mut garden.regions[garden.plots[y][x].region];
+                region.area += 1;
+                region.perimeter += perimeter;
+                region.sides += sides;
+            }
+        }
+
+        return garden;
+    }
+
+    fn same_region(&self, x: usize, y: usize, dx: i32, dy: i32) -> bool {
+        let other_x = x as i32 + dx;
+        let other_y = y as i32 + dy;
+        if other_x < 0
+            || other_y < 0
+            || other_x >= self.width as i32
+            || other_y >= self.height as i32
+        {
+            return false;
+        }
+        return self.plots[other_y as usize][other_x as usize].region == self.plots[y][x].region;
+    }
+
+    fn fences(&self, x: usize, y: usize) -> usize {
+        return [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .iter()
+            .filter(|(dx, dy)| !self.same_region(x, y, *dx, *dy))
+            .count();
+    }
+
+    // A polygon has as many sides as corners. Each plot checks its four diagonals: it is an
+    // outer corner when neither orthogonal neighbour belongs to the region, and an inner corner
+    // when both do but the diagonal plot does not.
+    fn corners(&self, x: usize, y: usize) -> usize {
+        return [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+            .iter()
+            .filter(|(dx, dy)| {
+                let horizontal = self.same_region(x, y, *dx, 0);
+                let vertical = self.same_region(x, y, 0, *dy);
+                let diagonal = self.same_region(x, y, *dx, *dy);
+                (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+            })
+            .count();
+    }
+
+    fn total_price(&self, pricing: Pricing) -> usize {
+        return self
+            .regions
+            .iter()
+            .map(|region| region.price(pricing))
+            .sum::<usize>();
+    }
+
+    fn _report(&self, pricing: Pricing) -> String {
+        return self
+            .regions
+            .iter()
+            .map(|region| {
+                format!(
+                    "{}: area {}, perimeter {}, sides {}, price {}",
+                    region._crop,
+                    region.area,
+                    region.perimeter,
+                    region.sides,
+                    region.price(pricing)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+impl Region {
+    fn price(&self, pricing: Pricing) -> usize {
+        return match pricing {
+            Pricing::Perimeter => self.area * self.perimeter,
+            Pricing::Sides => self.area * self.sides,
+        };
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -155,4 +208,32 @@ mod tests {
 
         assert_eq!("1206", &actual);
     }
+
+    #[rstest]
+    #[case("AAAA\nBBCD\nBBCC\nEEEC", 140, 80)]
+    #[case("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", 772, 436)]
+    #[case("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE", 692, 236)]
+    #[case("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA", 1184, 368)]
+    fn pricing_works(#[case] input: &str, #[case] perimeter: usize, #[case] sides: usize) {
+        let garden = Garden::parse(input);
+
+        assert_eq!(perimeter, garden.total_price(Pricing::Perimeter));
+        assert_eq!(sides, garden.total_price(Pricing::Sides));
+    }
+
+    #[test]
+    fn report_works() {
+        let garden = Garden::parse("AAAA\nBBCD\nBBCC\nEEEC");
+
+        let actual = garden._report(Pricing::Sides);
+
+        assert_eq!(
+            "A: area 4, perimeter 10, sides 4, price 16
+B: area 4, perimeter 8, sides 4, price 16
+C: area 4, perimeter 10, sides 8, price 32
+D: area 1, perimeter 4, sides 4, price 4
+E: area 3, perimeter 8, sides 4, price 12",
+            actual
+        );
+    }
 }
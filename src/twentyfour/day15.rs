@@ -3,14 +3,18 @@ use crate::create_advent_day;
 create_advent_day!("2024", "15");
 
 fn part1_with_input(input: &str) -> i64 {
-    let mut warehouse = parse_warehouse(input);
-    for direction in warehouse.directions.clone().iter() {
-        warehouse.march(*direction);
-    }
+    let mut warehouse = parse_warehouse(input, false);
+    warehouse.follow_directions();
+    return warehouse.calculate_gps() as i64;
+}
+
+fn part2_with_input(input: &str) -> i64 {
+    let mut warehouse = parse_warehouse(input, true);
+    warehouse.follow_directions();
     return warehouse.calculate_gps() as i64;
 }
 
-fn parse_warehouse(input: &str) -> Warehouse {
+fn parse_warehouse(input: &str, wide: bool) -> Warehouse {
     let mut map: Vec<Vec<char>> = Vec::new();
     let mut directions: Vec<char> = Vec::new();
     let mut found_empty = false;
@@ -22,12 +26,22 @@ fn parse_warehouse(input: &str) -> Warehouse {
             continue;
         }
         if !found_empty {
-            map.push(line.chars().collect());
-            if line.contains("@") {
-                let row_num = line.find("@").unwrap();
+            let row = if wide {
+                line.chars()
+                    .flat_map(|letter| match letter {
+                        'O' => ['[', ']'],
+                        '@' => ['@', '.'],
+                        _ => [letter, letter],
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                line.chars().collect()
+            };
+            if let Some(row_num) = row.iter().position(|letter| *letter == '@') {
                 guard_x = row_num;
                 guard_y = line_num;
             }
+            map.push(row);
         } else {
             directions.extend(line.chars());
         }
@@ -48,7 +62,7 @@ struct Warehouse {
 }
 
 impl Warehouse {
-    fn _debug(&self) {
+    fn _debug(&self) -> String {
         let lines = self
             .map
             .iter()
@@ -57,114 +71,70 @@ impl Warehouse {
                 value
             })
             .collect::<Vec<String>>();
-        let print_me = lines.join("\n");
-        println!("{print_me}");
+        return lines.join("\n");
     }
 
-    fn march(&mut self, direction: char) {
-        match direction {
-            '<' => self.march_left(),
-            '>' => self.march_right(),
-            '^' => self.march_up(),
-            'v' => self.march_down(),
-            _ => todo!("invalid direction"),
+    fn follow_directions(&mut self) {
+        for direction in self.directions.clone().iter() {
+            self.march(*direction);
         }
     }
 
-    fn march_up(&mut self) {
-        let mut lookahead = (self.guard_x, self.guard_y);
-        let can_move: bool;
-        loop {
-            if self.map[lookahead.1][lookahead.0] == '.' {
-                can_move = true;
-                break;
-            } else if self.map[lookahead.1][lookahead.0] == '#' {
-                can_move = false;
-                break;
-            }
-            lookahead.1 -= 1;
-        }
-        if !can_move {
-            return;
-        }
-        self.map[self.guard_y][self.guard_x] = '.';
-        self.map[self.guard_y - 1][self.guard_x] = '@';
-        self.guard_y -= 1;
-        if lookahead.1 < self.guard_y {
-            self.map[lookahead.1][lookahead.0] = 'O';
-        }
-    }
+    fn march(&mut self, direction: char) {
+        let (dx, dy) = match direction {
+            '<' => (-1, 0),
+            '>' => (1, 0),
+            '^' => (0, -1),
+            'v' => (0, 1),
+            _ => todo!("invalid direction"),
+        };
+        let pushed = match self.pushed_cells(dx, dy) {
+            Some(value) => value,
+            None => return,
+        };
 
-    fn march_down(&mut self) {
-        let mut lookahead = (self.guard_x, self.guard_y);
-        let can_move: bool;
-        loop {
-            if self.map[lookahead.1][lookahead.0] == '.' {
-                can_move = true;
-                break;
-            } else if self.map[lookahead.1][lookahead.0] == '#' {
-                can_move = false;
-                break;
-            }
-            lookahead.1 += 1;
-        }
-        if !can_move {
-            return;
+        let moved = pushed
+            .iter()
+            .map(|(x, y)| (*x, *y, self.map[*y][*x]))
+            .collect::<Vec<_>>();
+        for (x, y, _) in moved.iter() {
+            self.map[*y][*x] = '.';
         }
-        self.map[self.guard_y][self.guard_x] = '.';
-        self.map[self.guard_y + 1][self.guard_x] = '@';
-        self.guard_y += 1;
-        if lookahead.1 > self.guard_y {
-            self.map[lookahead.1][lookahead.0] = 'O';
+        for (x, y, letter) in moved.iter() {
+            self.map[(*y as i32 + dy) as usize][(*x as i32 + dx) as usize] = *letter;
         }
+        self.guard_x = (self.guard_x as i32 + dx) as usize;
+        self.guard_y = (self.guard_y as i32 + dy) as usize;
     }
 
-    fn march_left(&mut self) {
-        let mut lookahead = (self.guard_x, self.guard_y);
-        let can_move: bool;
-        loop {
-            if self.map[lookahead.1][lookahead.0] == '.' {
-                can_move = true;
-                break;
-            } else if self.map[lookahead.1][lookahead.0] == '#' {
-                can_move = false;
-                break;
+    // Breadth-first walk from the guard over everything that would be shoved. A wide box pushed
+    // vertically drags its other half along, which can fan out into a whole pyramid of boxes.
+    // Returns None as soon as any of them would hit a wall.
+    fn pushed_cells(&self, dx: i32, dy: i32) -> Option<Vec<(usize, usize)>> {
+        let mut pushed = vec![(self.guard_x, self.guard_y)];
+        let mut index = 0;
+        while index < pushed.len() {
+            let (x, y) = pushed[index];
+            index += 1;
+            let next_x = (x as i32 + dx) as usize;
+            let next_y = (y as i32 + dy) as usize;
+            let mut next = match self.map[next_y][next_x] {
+                '#' => return None,
+                'O' => vec![(next_x, next_y)],
+                '[' => vec![(next_x, next_y), (next_x + 1, next_y)],
+                ']' => vec![(next_x, next_y), (next_x - 1, next_y)],
+                _ => vec![],
+            };
+            if dy == 0 {
+                next.truncate(1);
             }
-            lookahead.0 -= 1;
-        }
-        if !can_move {
-            return;
-        }
-        self.map[self.guard_y][self.guard_x] = '.';
-        self.map[self.guard_y][self.guard_x - 1] = '@';
-        self.guard_x -= 1;
-        if lookahead.0 < self.guard_x {
-            self.map[lookahead.1][lookahead.0] = 'O';
-        }
-    }
-
-    fn march_right(&mut self) {
-        let mut lookahead = (self.guard_x, self.guard_y);
-        let can_move: bool;
-        loop {
-            if self.map[lookahead.1][lookahead.0] == '.' {
-                can_move = true;
-                break;
-            } else if self.map[lookahead.1][lookahead.0] == '#' {
-                can_move = false;
-                break;
+            for cell in next {
+                if !pushed.contains(&cell) {
+                    pushed.push(cell);
+                }
             }
-            lookahead.0 += 1;
-        }
-        if !can_move {
-            return;
-        }
-        self.map[self.guard_y][self.guard_x] = '.';
-        self.map[self.guard_y][self.guard_x + 1] = '@';
-        self.guard_x += 1;
-        if lookahead.0 > self.guard_x {
-            self.map[lookahead.1][lookahead.0] = 'O';
         }
+        return Some(pushed);
     }
 
     fn calculate_gps(&self) -> usize {
@@ -176,7 +146,7 @@ impl Warehouse {
                 line.iter()
                     .enumerate()
                     .map(|(row_num, letter)| {
-                        if *letter != 'O' {
+                        if *letter != 'O' && *letter != '[' {
                             0
                         } else {
                             (100 * line_num) + row_num
@@ -188,10 +158,6 @@ impl Warehouse {
     }
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +179,35 @@ mod tests {
     fn part2_works() {
         let actual = create("test1.txt").solve_part2();
 
-        assert_eq!("0", &actual);
+        assert_eq!("9021", &actual);
+    }
+
+    #[test]
+    fn wide_boxes_push_each_other() {
+        let mut warehouse = parse_warehouse(
+            "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^",
+            true,
+        );
+
+        warehouse.follow_directions();
+
+        assert_eq!(
+            "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############",
+            warehouse._debug()
+        );
     }
 }
@@ -1,30 +1,204 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
 use crate::create_advent_day;
 
 create_advent_day!("2024", "16");
 
-fn part1_with_input(_input: &str) -> i64 {
-    return 0;
+fn part1_with_input(input: &str) -> i64 {
+    let maze = ReindeerMaze::parse(input);
+    let (score, _) = maze.best_paths();
+
+    return score as i64;
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+fn part2_with_input(input: &str) -> i64 {
+    let maze = ReindeerMaze::parse(input);
+    let (_, tiles) = maze.best_paths();
+
+    return tiles.iter().filter(|tile| **tile).count() as i64;
+}
+
+const STEP_COST: usize = 1;
+const TURN_COST: usize = 1000;
+
+// Facing east, south, west, north; turning clockwise adds one.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+struct ReindeerMaze {
+    walls: Vec<bool>,
+    width: usize,
+    height: usize,
+    start: usize,
+    end: usize,
+}
+
+impl ReindeerMaze {
+    fn parse(input: &str) -> ReindeerMaze {
+        let mut walls = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+        let mut height = 0;
+        for line in input.lines() {
+            for letter in line.chars() {
+                match letter {
+                    'S' => start = walls.len(),
+                    'E' => end = walls.len(),
+                    _ => {}
+                }
+                walls.push(letter == '#');
+            }
+            height += 1;
+        }
+        let width = walls.len() / height;
+
+        return ReindeerMaze {
+            walls,
+            width,
+            height,
+            start,
+            end,
+        };
+    }
+
+    // States are tile * 4 + facing.
+    fn moves(&self, state: usize) -> Vec<(usize, usize)> {
+        let tile = state / 4;
+        let facing = state % 4;
+        let mut moves = vec![
+            (tile * 4 + (facing + 1) % 4, TURN_COST),
+            (tile * 4 + (facing + 3) % 4, TURN_COST),
+        ];
+        let (dx, dy) = DIRECTIONS[facing];
+        let x = (tile % self.width) as i32 + dx;
+        let y = (tile / self.width) as i32 + dy;
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let ahead = y as usize * self.width + x as usize;
+            if !self.walls[ahead] {
+                moves.push((ahead * 4 + facing, STEP_COST));
+            }
+        }
+        return moves;
+    }
+
+    // Dijkstra over (tile, facing) that remembers every predecessor reaching a state at its best
+    // score, so walking those links back from the end finds every tile on any best path.
+    fn best_paths(&self) -> (usize, Vec<bool>) {
+        let state_count = self.walls.len() * 4;
+        let mut scores = vec![usize::MAX; state_count];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); state_count];
+        let mut queue = BinaryHeap::new();
+        scores[self.start * 4] = 0;
+        queue.push(Reverse((0, self.start * 4)));
+
+        while let Some(Reverse((score, state))) = queue.pop() {
+            if score > scores[state] {
+                continue;
+            }
+            for (next, cost) in self.moves(state) {
+                let next_score = score + cost;
+                if next_score < scores[next] {
+                    scores[next] = next_score;
+                    predecessors[next] = vec![state];
+                    queue.push(Reverse((next_score, next)));
+                } else if next_score == scores[next] {
+                    predecessors[next].push(state);
+                }
+            }
+        }
+
+        let best = (0..4)
+            .map(|facing| scores[self.end * 4 + facing])
+            .min()
+            .unwrap();
+        let mut on_path = vec![false; state_count];
+        let mut to_visit = (0..4)
+            .map(|facing| self.end * 4 + facing)
+            .filter(|state| scores[*state] == best)
+            .collect::<Vec<_>>();
+        while let Some(state) = to_visit.pop() {
+            if on_path[state] {
+                continue;
+            }
+            on_path[state] = true;
+            to_visit.extend(predecessors[state].iter());
+        }
+        let tiles = on_path
+            .chunks(4)
+            .map(|facings| facings.iter().any(|state| *state))
+            .collect::<Vec<_>>();
+
+        return (best, tiles);
+    }
+
+    fn _render_best_paths(&self) -> String {
+        let (_, tiles) = self.best_paths();
+        return (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let tile = y * self.width + x;
+                        if tiles[tile] {
+                            'O'
+                        } else if self.walls[tile] {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
-    #[test]
-    fn part1_works() {
-        let actual = create("test.txt").solve_part1();
+    #[rstest]
+    #[case("test.txt", "7036")]
+    #[case("test2.txt", "11048")]
+    fn part1_works(#[case] file_name: &str, #[case] expected: &str) {
+        let actual = create(file_name).solve_part1();
+
+        assert_eq!(expected, &actual);
+    }
+
+    #[rstest]
+    #[case("test.txt", "45")]
+    #[case("test2.txt", "64")]
+    fn part2_works(#[case] file_name: &str, #[case] expected: &str) {
+        let actual = create(file_name).solve_part2();
 
-        assert_eq!("0", &actual);
+        assert_eq!(expected, &actual);
     }
 
     #[test]
-    fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+    fn render_best_paths_works() {
+        let maze = ReindeerMaze::parse(&utils::read_file("2024/day16", "test.txt"));
+
+        let actual = maze._render_best_paths();
 
-        assert_eq!("0", &actual);
+        assert_eq!(
+            "###############
+#.......#....O#
+#.#.###.#.###O#
+#.....#.#...#O#
+#.###.#####.#O#
+#.#.#.......#O#
+#.#.#####.###O#
+#..OOOOOOOOO#O#
+###O#O#####O#O#
+#OOO#O....#O#O#
+#O#O#O###.#O#O#
+#OOOOO#...#O#O#
+#O###.#.#.#O#O#
+#O..#.....#OOO#
+###############",
+            actual
+        );
     }
 }
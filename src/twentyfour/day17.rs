@@ -2,29 +2,212 @@ use crate::create_advent_day;
 
 create_advent_day!("2024", "17");
 
-fn part1_with_input(_input: &str) -> i64 {
-    return 0;
+fn part1_with_input(input: &str) -> String {
+    let computer = Computer::parse(input);
+
+    return join(&computer.run());
+}
+
+fn part2_with_input(input: &str) -> u64 {
+    let computer = Computer::parse(input);
+
+    return computer.find_quine().expect("no value of A makes a quine");
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+fn join(output: &[u8]) -> String {
+    return output
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+}
+
+#[derive(Clone)]
+struct Computer {
+    a: u64,
+    b: u64,
+    c: u64,
+    program: Vec<u8>,
+}
+
+impl Computer {
+    fn parse(input: &str) -> Computer {
+        let mut registers = [0u64; 3];
+        let mut program = Vec::new();
+        for line in input.lines() {
+            if let Some((label, value)) = line.split_once(": ") {
+                match label {
+                    "Register A" => registers[0] = value.parse().unwrap(),
+                    "Register B" => registers[1] = value.parse().unwrap(),
+                    "Register C" => registers[2] = value.parse().unwrap(),
+                    "Program" => {
+                        program = value.split(',').map(|num| num.parse().unwrap()).collect()
+                    }
+                    _ => panic!("unknown line {line}"),
+                }
+            }
+        }
+        return Computer {
+            a: registers[0],
+            b: registers[1],
+            c: registers[2],
+            program,
+        };
+    }
+
+    fn combo(&self, operand: u8) -> u64 {
+        return match operand {
+            0..=3 => operand as u64,
+            4 => self.a,
+            5 => self.b,
+            6 => self.c,
+            _ => panic!("combo operand {operand} is reserved"),
+        };
+    }
+
+    fn run(&self) -> Vec<u8> {
+        return self.clone().execute();
+    }
+
+    fn run_with_a(&self, a: u64) -> Vec<u8> {
+        let mut computer = self.clone();
+        computer.a = a;
+        return computer.execute();
+    }
+
+    fn execute(&mut self) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut pointer = 0;
+        while pointer + 1 < self.program.len() {
+            let opcode = self.program[pointer];
+            let operand = self.program[pointer + 1];
+            pointer += 2;
+            match opcode {
+                0 => self.a >>= self.combo(operand),
+                1 => self.b ^= operand as u64,
+                2 => self.b = self.combo(operand) % 8,
+                3 => {
+                    if self.a != 0 {
+                        pointer = operand as usize;
+                    }
+                }
+                4 => self.b ^= self.c,
+                5 => output.push((self.combo(operand) % 8) as u8),
+                6 => self.b = self.a >> self.combo(operand),
+                7 => self.c = self.a >> self.combo(operand),
+                _ => panic!("unknown opcode {opcode}"),
+            }
+        }
+        return output;
+    }
+
+    // Programs of this shape print one value per loop and shift A right three bits each time,
+    // so the last output only depends on the highest octal digit of A. Building A one digit at
+    // a time from the end of the program keeps the search tiny.
+    fn find_quine(&self) -> Option<u64> {
+        let mut candidates = vec![0u64];
+        for index in (0..self.program.len()).rev() {
+            let expected = &self.program[index..];
+            candidates = candidates
+                .iter()
+                .flat_map(|prefix| (0..8).map(move |digit| prefix * 8 + digit))
+                .filter(|a| self.run_with_a(*a) == expected)
+                .collect();
+        }
+        return candidates
+            .into_iter()
+            .filter(|a| *a != 0 && self.run_with_a(*a) == self.program)
+            .min();
+    }
+
+    fn _disassemble(&self) -> String {
+        let combo = |operand: u8| match operand {
+            0..=3 => operand.to_string(),
+            4 => "A".to_owned(),
+            5 => "B".to_owned(),
+            6 => "C".to_owned(),
+            _ => "?".to_owned(),
+        };
+        return self
+            .program
+            .chunks(2)
+            .enumerate()
+            .map(|(index, instruction)| {
+                let operand = *instruction.get(1).unwrap_or(&0);
+                let text = match instruction[0] {
+                    0 => format!("adv {}    A = A >> {}", operand, combo(operand)),
+                    1 => format!("bxl {}    B = B ^ {}", operand, operand),
+                    2 => format!("bst {}    B = {} % 8", operand, combo(operand)),
+                    3 => format!("jnz {}    if A != 0 goto {}", operand, operand),
+                    4 => format!("bxc {}    B = B ^ C", operand),
+                    5 => format!("out {}    print {} % 8", operand, combo(operand)),
+                    6 => format!("bdv {}    B = A >> {}", operand, combo(operand)),
+                    7 => format!("cdv {}    C = A >> {}", operand, combo(operand)),
+                    opcode => format!("??? {}    unknown opcode {}", operand, opcode),
+                };
+                format!("{:2}: {}", index * 2, text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
     fn part1_works() {
         let actual = create("test.txt").solve_part1();
 
-        assert_eq!("0", &actual);
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", &actual);
     }
 
     #[test]
     fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+        let actual = create("test2.txt").solve_part2();
+
+        assert_eq!("117440", &actual);
+    }
+
+    #[rstest]
+    #[case((10, 0, 0), "5,0,5,1,5,4", "0,1,2", (10, 0, 0))]
+    #[case((2024, 0, 0), "0,1,5,4,3,0", "4,2,5,6,7,7,7,7,3,1,0", (0, 0, 0))]
+    #[case((0, 0, 9), "2,6", "", (0, 1, 9))]
+    #[case((0, 29, 0), "1,7", "", (0, 26, 0))]
+    #[case((0, 2024, 43690), "4,0", "", (0, 44354, 43690))]
+    fn execute_works(
+        #[case] registers: (u64, u64, u64),
+        #[case] program: &str,
+        #[case] expected_output: &str,
+        #[case] expected_registers: (u64, u64, u64),
+    ) {
+        let mut computer = Computer {
+            a: registers.0,
+            b: registers.1,
+            c: registers.2,
+            program: program.split(',').map(|num| num.parse().unwrap()).collect(),
+        };
+
+        let output = computer.execute();
+
+        assert_eq!(expected_output, join(&output));
+        assert_eq!(expected_registers, (computer.a, computer.b, computer.c));
+    }
+
+    #[test]
+    fn disassemble_works() {
+        let computer = Computer::parse(&utils::read_file("2024/day17", "test2.txt"));
+
+        let actual = computer._disassemble();
 
-        assert_eq!("0", &actual);
+        assert_eq!(
+            " 0: adv 3    A = A >> 3
+ 2: out 4    print A % 8
+ 4: jnz 0    if A != 0 goto 0",
+            actual
+        );
     }
 }
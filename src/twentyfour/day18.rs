@@ -1,13 +1,105 @@
+use std::collections::VecDeque;
+
 use crate::create_advent_day;
 
 create_advent_day!("2024", "18");
 
-fn part1_with_input(_input: &str) -> i64 {
-    return 0;
+fn part1_with_input(input: &str) -> i64 {
+    return shortest_path_after(input, 71, 1024).expect("exit is unreachable") as i64;
+}
+
+fn part2_with_input(input: &str) -> String {
+    return first_blocking_byte(input, 71);
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+fn shortest_path_after(input: &str, size: usize, fallen: usize) -> Option<usize> {
+    let memory = MemorySpace::parse(input, size);
+    return memory.shortest_path(fallen);
+}
+
+fn first_blocking_byte(input: &str, size: usize) -> String {
+    let memory = MemorySpace::parse(input, size);
+    let (x, y) = memory.first_blocking_byte().expect("exit is never cut off");
+    return format!("{x},{y}");
+}
+
+struct MemorySpace {
+    size: usize,
+    bytes: Vec<(usize, usize)>,
+}
+
+impl MemorySpace {
+    fn parse(input: &str, size: usize) -> MemorySpace {
+        let bytes = input
+            .lines()
+            .map(|line| {
+                let (x, y) = line.split_once(',').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect();
+        return MemorySpace { size, bytes };
+    }
+
+    fn shortest_path(&self, fallen: usize) -> Option<usize> {
+        let mut corrupted = vec![false; self.size * self.size];
+        for (x, y) in self.bytes.iter().take(fallen) {
+            corrupted[y * self.size + x] = true;
+        }
+        if corrupted[0] {
+            return None;
+        }
+
+        let exit = self.size * self.size - 1;
+        let mut distances = vec![usize::MAX; self.size * self.size];
+        distances[0] = 0;
+        let mut queue = VecDeque::from([0]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == exit {
+                return Some(distances[cell]);
+            }
+            let x = cell % self.size;
+            let y = cell / self.size;
+            let mut neighbors = Vec::new();
+            if x > 0 {
+                neighbors.push(cell - 1);
+            }
+            if x < self.size - 1 {
+                neighbors.push(cell + 1);
+            }
+            if y > 0 {
+                neighbors.push(cell - self.size);
+            }
+            if y < self.size - 1 {
+                neighbors.push(cell + self.size);
+            }
+            for neighbor in neighbors {
+                if !corrupted[neighbor] && distances[neighbor] == usize::MAX {
+                    distances[neighbor] = distances[cell] + 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        return None;
+    }
+
+    // Once the exit is cut off it stays cut off, so binary search for the smallest number of
+    // fallen bytes with no path; the last of those bytes is the culprit.
+    fn first_blocking_byte(&self) -> Option<(usize, usize)> {
+        if self.shortest_path(self.bytes.len()).is_some() {
+            return None;
+        }
+        let mut reachable = 0;
+        let mut blocked = self.bytes.len();
+        while blocked - reachable > 1 {
+            let middle = (reachable + blocked) / 2;
+            if self.shortest_path(middle).is_some() {
+                reachable = middle;
+            } else {
+                blocked = middle;
+            }
+        }
+        return Some(self.bytes[blocked - 1]);
+    }
 }
 
 #[cfg(test)]
@@ -16,15 +108,29 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let actual = create("test.txt").solve_part1();
+        let input = utils::read_file("2024/day18", "test.txt");
+
+        let actual = shortest_path_after(&input, 7, 12);
 
-        assert_eq!("0", &actual);
+        assert_eq!(Some(22), actual);
     }
 
     #[test]
     fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+        let input = utils::read_file("2024/day18", "test.txt");
+
+        let actual = first_blocking_byte(&input, 7);
+
+        assert_eq!("6,1", &actual);
+    }
+
+    #[test]
+    fn shortest_path_is_none_once_blocked() {
+        let input = utils::read_file("2024/day18", "test.txt");
+        let memory = MemorySpace::parse(&input, 7);
 
-        assert_eq!("0", &actual);
+        assert_eq!(Some(12), memory.shortest_path(0));
+        assert!(memory.shortest_path(20).is_some());
+        assert_eq!(None, memory.shortest_path(21));
     }
 }
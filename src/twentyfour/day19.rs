@@ -2,29 +2,181 @@ use crate::create_advent_day;
 
 create_advent_day!("2024", "19");
 
-fn part1_with_input(_input: &str) -> i64 {
-    return 0;
+fn part1_with_input(input: &str) -> i64 {
+    let (towels, designs) = parse(input);
+    return designs
+        .iter()
+        .filter(|design| towels.arrangements(design) > 0)
+        .count() as i64;
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+fn part2_with_input(input: &str) -> u64 {
+    let (towels, designs) = parse(input);
+    return designs
+        .iter()
+        .map(|design| towels.arrangements(design))
+        .sum::<u64>();
+}
+
+fn parse(input: &str) -> (Towels, Vec<&str>) {
+    let mut lines = input.lines();
+    let towels = Towels::parse(lines.next().unwrap());
+    let designs = lines.filter(|line| !line.is_empty()).collect();
+    return (towels, designs);
+}
+
+const COLORS: &[u8; 5] = b"wubrg";
+
+fn color_index(color: u8) -> usize {
+    return COLORS
+        .iter()
+        .position(|known| *known == color)
+        .unwrap_or_else(|| panic!("unknown stripe color {}", color as char));
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: [Option<usize>; 5],
+    terminal: bool,
+}
+
+struct Towels {
+    nodes: Vec<TrieNode>,
+}
+
+impl Towels {
+    fn parse(input: &str) -> Towels {
+        let mut towels = Towels {
+            nodes: vec![TrieNode::default()],
+        };
+        input
+            .split(", ")
+            .for_each(|pattern| towels.insert(pattern.trim()));
+        return towels;
+    }
+
+    fn insert(&mut self, pattern: &str) {
+        let mut node = 0;
+        for color in pattern.bytes() {
+            let index = color_index(color);
+            node = match self.nodes[node].children[index] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[index] = Some(child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].terminal = true;
+    }
+
+    // Lengths of every towel pattern that matches the start of `design`.
+    fn prefix_lengths(&self, design: &[u8]) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut node = 0;
+        for (offset, color) in design.iter().enumerate() {
+            node = match COLORS.iter().position(|known| known == color) {
+                Some(index) => match self.nodes[node].children[index] {
+                    Some(child) => child,
+                    None => break,
+                },
+                None => break,
+            };
+            if self.nodes[node].terminal {
+                lengths.push(offset + 1);
+            }
+        }
+        return lengths;
+    }
+
+    // ways[i] counts the arrangements of design[i..], filled in from the end of the design.
+    fn arrangements(&self, design: &str) -> u64 {
+        let design = design.as_bytes();
+        let mut ways = vec![0u64; design.len() + 1];
+        ways[design.len()] = 1;
+        for start in (0..design.len()).rev() {
+            ways[start] = self
+                .prefix_lengths(&design[start..])
+                .iter()
+                .map(|length| ways[start + length])
+                .sum();
+        }
+        return ways[0];
+    }
+
+    fn _decompositions<'a>(&self, design: &'a str, limit: usize) -> Vec<Vec<&'a str>> {
+        if limit == 0 {
+            return Vec::new();
+        }
+        if design.is_empty() {
+            return vec![Vec::new()];
+        }
+        let mut decompositions = Vec::new();
+        for length in self.prefix_lengths(design.as_bytes()) {
+            for mut rest in self._decompositions(&design[length..], limit - decompositions.len()) {
+                rest.insert(0, &design[..length]);
+                decompositions.push(rest);
+                if decompositions.len() == limit {
+                    return decompositions;
+                }
+            }
+        }
+        return decompositions;
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
     fn part1_works() {
         let actual = create("test.txt").solve_part1();
 
-        assert_eq!("0", &actual);
+        assert_eq!("6", &actual);
     }
 
     #[test]
     fn part2_works() {
         let actual = create("test.txt").solve_part2();
 
-        assert_eq!("0", &actual);
+        assert_eq!("16", &actual);
+    }
+
+    #[rstest]
+    #[case("brwrr", 2)]
+    #[case("bggr", 1)]
+    #[case("gbbr", 4)]
+    #[case("rrbgbr", 6)]
+    #[case("ubwu", 0)]
+    #[case("bwurrg", 1)]
+    #[case("brgr", 2)]
+    #[case("bbrgwb", 0)]
+    fn arrangements_works(#[case] design: &str, #[case] expected: u64) {
+        let towels = Towels::parse("r, wr, b, g, bwu, rb, gb, br");
+
+        assert_eq!(expected, towels.arrangements(design));
+    }
+
+    #[test]
+    fn decompositions_works() {
+        let towels = Towels::parse("r, wr, b, g, bwu, rb, gb, br");
+
+        let actual = towels._decompositions("gbbr", 10);
+
+        assert_eq!(
+            vec![
+                vec!["g", "b", "b", "r"],
+                vec!["g", "b", "br"],
+                vec!["gb", "b", "r"],
+                vec!["gb", "br"],
+            ],
+            actual
+        );
+        assert_eq!(2, towels._decompositions("gbbr", 2).len());
     }
 }
@@ -1,39 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, VecDeque};
 
 use crate::create_advent_day;
 
 create_advent_day!("2024", "20");
 
 fn part1_with_input(input: &str) -> i64 {
-    return part1_with_threshold(input, 100);
+    return count_cheats(input, 2, 100) as i64;
 }
 
-fn part1_with_threshold(input: &str, threshold: usize) -> i64 {
-    let maze = parse_maze(input);
-    let path = maze.solve();
-
-    let mut cheats: HashMap<usize, usize> = HashMap::new();
-    let num_picosconds = path.len();
-    for i in 0..num_picosconds - 3 {
-        for j in (i + 3)..num_picosconds {
-            let distance = distance(path[i], path[j]);
-            if distance == 2 {
-                *cheats.entry((j - i) - 2).or_default() += 1;
-            }
-        }
-    }
-
-    return cheats
-        .into_iter()
-        .filter(|(key, _)| *key >= threshold)
-        .map(|(_, value)| value)
-        .sum::<usize>() as i64;
+fn part2_with_input(input: &str) -> i64 {
+    return count_cheats(input, 20, 100) as i64;
 }
 
-fn distance(point1: (usize, usize), point2: (usize, usize)) -> usize {
-    let x_diff = usize::abs_diff(point1.0, point2.0);
-    let y_diff = usize::abs_diff(point1.1, point2.1);
-    return x_diff + y_diff;
+fn count_cheats(input: &str, max_cheat: usize, threshold: usize) -> usize {
+    let maze = parse_maze(input);
+    return maze
+        .cheat_histogram(max_cheat)
+        .range(threshold..)
+        .map(|(_, count)| count)
+        .sum::<usize>();
 }
 
 struct Maze {
@@ -43,27 +28,66 @@ struct Maze {
 }
 
 impl Maze {
-    fn solve(&self) -> Vec<(usize, usize)> {
-        let mut last = self.start.clone();
-        let mut next = self.neighbors(last)[0];
-        let mut path = vec![last, next];
-        loop {
-            if self.end == next {
-                break;
+    fn distances_from(&self, origin: (usize, usize)) -> Vec<Vec<usize>> {
+        let mut distances = vec![vec![usize::MAX; self.layout[0].len()]; self.layout.len()];
+        distances[origin.1][origin.0] = 0;
+        let mut queue = VecDeque::from([origin]);
+        while let Some(location) = queue.pop_front() {
+            let distance = distances[location.1][location.0];
+            for neighbor in self.neighbors(location) {
+                if distances[neighbor.1][neighbor.0] == usize::MAX {
+                    distances[neighbor.1][neighbor.0] = distance + 1;
+                    queue.push_back(neighbor);
+                }
             }
-            let neighbors = self
-                .neighbors(next)
-                .into_iter()
-                .filter(|&point| point != last)
-                .collect::<Vec<_>>();
-            if neighbors.len() != 1 {
-                println!("uh oh");
+        }
+        return distances;
+    }
+
+    // A cheat is a jump between two track cells at most `max_cheat` apart in Manhattan distance,
+    // so every track cell only needs to look at the diamond of offsets around it. Distances are
+    // measured from both ends so the savings stay correct even if the track branches.
+    fn cheat_histogram(&self, max_cheat: usize) -> BTreeMap<usize, usize> {
+        let from_start = self.distances_from(self.start);
+        let from_end = self.distances_from(self.end);
+        let best = from_start[self.end.1][self.end.0];
+        let radius = max_cheat as i32;
+        let offsets = (-radius..=radius)
+            .flat_map(|dy| {
+                let width = radius - dy.abs();
+                (-width..=width).map(move |dx| (dx, dy))
+            })
+            .filter(|(dx, dy)| dx.abs() + dy.abs() >= 2)
+            .collect::<Vec<_>>();
+
+        let mut histogram = BTreeMap::new();
+        for (y, row) in from_start.iter().enumerate() {
+            for (x, before) in row.iter().enumerate() {
+                if *before == usize::MAX {
+                    continue;
+                }
+                for (dx, dy) in offsets.iter() {
+                    let other_x = x as i32 + dx;
+                    let other_y = y as i32 + dy;
+                    if other_x < 0
+                        || other_y < 0
+                        || other_y as usize >= from_end.len()
+                        || other_x as usize >= from_end[0].len()
+                    {
+                        continue;
+                    }
+                    let after = from_end[other_y as usize][other_x as usize];
+                    if after == usize::MAX {
+                        continue;
+                    }
+                    let length = before + (dx.abs() + dy.abs()) as usize + after;
+                    if length < best {
+                        *histogram.entry(best - length).or_default() += 1;
+                    }
+                }
             }
-            path.push(neighbors[0]);
-            last = next;
-            next = neighbors[0];
         }
-        return path;
+        return histogram;
     }
 
     fn neighbors(&self, location: (usize, usize)) -> Vec<(usize, usize)> {
@@ -110,10 +134,6 @@ fn parse_maze(input: &str) -> Maze {
     return Maze { layout, start, end };
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
-}
-
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -125,17 +145,78 @@ mod tests {
     #[case(4, 30)]
     #[case(6, 16)]
     #[case(8, 14)]
-    fn part1_works(#[case] threshold: usize, #[case] expected: i64) {
+    fn part1_works(#[case] threshold: usize, #[case] expected: usize) {
+        let input = utils::read_file("2024/day20", "test.txt");
+        let actual = count_cheats(&input, 2, threshold);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[rstest]
+    #[case(50, 285)]
+    #[case(70, 41)]
+    #[case(76, 3)]
+    fn part2_works(#[case] threshold: usize, #[case] expected: usize) {
         let input = utils::read_file("2024/day20", "test.txt");
-        let actual = part1_with_threshold(&input, threshold);
+        let actual = count_cheats(&input, 20, threshold);
 
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+    fn cheat_histogram_works() {
+        let input = utils::read_file("2024/day20", "test.txt");
+        let maze = parse_maze(&input);
+
+        let actual = maze.cheat_histogram(2);
+
+        assert_eq!(
+            BTreeMap::from([
+                (2, 14),
+                (4, 14),
+                (6, 2),
+                (8, 4),
+                (10, 2),
+                (12, 3),
+                (20, 1),
+                (36, 1),
+                (38, 1),
+                (40, 1),
+                (64, 1)
+            ]),
+            actual
+        );
+    }
 
-        assert_eq!("0", &actual);
+    #[test]
+    fn long_cheat_histogram_works() {
+        let input = utils::read_file("2024/day20", "test.txt");
+        let maze = parse_maze(&input);
+
+        let actual = maze
+            .cheat_histogram(20)
+            .into_iter()
+            .filter(|(saved, _)| *saved >= 50)
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(
+            BTreeMap::from([
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3)
+            ]),
+            actual
+        );
     }
 }
@@ -4,245 +4,134 @@ use crate::create_advent_day;
 
 create_advent_day!("2024", "21");
 
-fn part1_with_input(_input: &str) -> i64 {
-    let directions = directions();
-    return _input
-        .lines()
-        .map(|line| complexity(line, &directions))
-        .sum::<usize>() as i64;
+fn part1_with_input(input: &str) -> i64 {
+    return total_complexity(input, 2) as i64;
 }
 
-fn first_robot(output: &str, directions: &HashMap<(char, char), String>) -> String {
-    // println!("translating {output}");
-    let mut output = output.chars().collect::<Vec<_>>();
-    output.insert(0, 'A');
-    let strings = output
-        .windows(2)
-        .map(|tuple| directions.get(&(tuple[0], tuple[1])).unwrap().clone())
-        .collect::<Vec<_>>();
-    // let debug = strings.join(",");
-    // println!("first sequence {debug}");
-    return strings.concat();
+fn part2_with_input(input: &str) -> i64 {
+    return total_complexity(input, 25) as i64;
 }
 
-fn second_robot(output: &str, directions: &HashMap<(char, char), String>) -> String {
-    let first_sequence = first_robot(output, directions);
-    let mut output = first_sequence.chars().collect::<Vec<_>>();
-    output.insert(0, 'A');
-    let strings = output
-        .windows(2)
-        .map(|tuple| directions.get(&(tuple[0], tuple[1])).unwrap().clone())
-        .collect::<Vec<_>>();
-    // let debug = strings.join(",");
-    // println!("second sequence {debug}");
-    return strings.concat();
-}
+const NUMERIC_LAYOUT: [&str; 4] = ["789", "456", "123", " 0A"];
+const DIRECTIONAL_LAYOUT: [&str; 2] = [" ^A", "<v>"];
 
-fn third_robot(output: &str, directions: &HashMap<(char, char), String>) -> String {
-    let second_sequence = second_robot(output, directions);
-    let mut output = second_sequence.chars().collect::<Vec<_>>();
-    output.insert(0, 'A');
-    let strings = output
-        .windows(2)
-        .map(|tuple| directions.get(&(tuple[0], tuple[1])).unwrap().clone())
-        .collect::<Vec<_>>();
-    // let debug = strings.join(",");
-    // println!("third sequence {debug}");
-    return strings.concat();
+fn total_complexity(input: &str, depth: usize) -> usize {
+    let mut chain = KeypadChain::new();
+    return input
+        .lines()
+        .map(|line| chain.complexity(line, depth))
+        .sum::<usize>();
 }
 
-fn complexity(output: &str, directions: &HashMap<(char, char), String>) -> usize {
-    let code = third_robot(output, directions);
-    let numeric = output.trim_end_matches("A").parse::<usize>().unwrap();
-    return code.len() * numeric;
+struct Keypad {
+    keys: HashMap<char, (i32, i32)>,
+    gap: (i32, i32),
 }
 
-fn directions() -> HashMap<(char, char), String> {
-    let mut numerical = numerical_directions();
-    let arrows = arrow_directions();
+impl Keypad {
+    fn parse(layout: &[&str]) -> Keypad {
+        let mut keys = HashMap::new();
+        let mut gap = (-1, -1);
+        for (y, row) in layout.iter().enumerate() {
+            for (x, key) in row.chars().enumerate() {
+                let position = (x as i32, y as i32);
+                if key == ' ' {
+                    gap = position;
+                } else {
+                    keys.insert(key, position);
+                }
+            }
+        }
+        return Keypad { keys, gap };
+    }
 
-    numerical.extend(arrows);
-    return numerical;
+    // Mixing horizontal and vertical presses never helps the robot upstream, so the only
+    // candidates worth considering are "all horizontal then all vertical" and the reverse,
+    // minus whichever one would sweep the arm over the gap.
+    fn moves(&self, from: char, to: char) -> Vec<String> {
+        let (from_x, from_y) = self.keys[&from];
+        let (to_x, to_y) = self.keys[&to];
+        let horizontal =
+            if to_x > from_x { ">" } else { "<" }.repeat(from_x.abs_diff(to_x) as usize);
+        let vertical = if to_y > from_y { "v" } else { "^" }.repeat(from_y.abs_diff(to_y) as usize);
+
+        let mut moves = Vec::new();
+        if (to_x, from_y) != self.gap {
+            moves.push(format!("{horizontal}{vertical}A"));
+        }
+        if (from_x, to_y) != self.gap {
+            moves.push(format!("{vertical}{horizontal}A"));
+        }
+        moves.dedup();
+        return moves;
+    }
 }
 
-fn numerical_directions() -> HashMap<(char, char), String> {
-    let mut map = HashMap::new();
-
-    // A
-    map.insert(('A', 'A'), "A".to_owned());
-    map.insert(('A', '0'), "<A".to_owned());
-    map.insert(('A', '1'), "^<<A".to_owned());
-    map.insert(('A', '2'), "^<A".to_owned());
-    map.insert(('A', '3'), "^A".to_owned());
-    map.insert(('A', '4'), "^^<<A".to_owned());
-    map.insert(('A', '5'), "^^<A".to_owned());
-    map.insert(('A', '6'), "^^A".to_owned());
-    map.insert(('A', '7'), "^^^<<A".to_owned());
-    map.insert(('A', '8'), "^^^<A".to_owned());
-    map.insert(('A', '9'), "^^^A".to_owned());
-    // 0
-    map.insert(('0', 'A'), ">A".to_owned());
-    map.insert(('0', '0'), "A".to_owned());
-    map.insert(('0', '1'), "^<A".to_owned());
-    map.insert(('0', '2'), "^A".to_owned());
-    map.insert(('0', '3'), "^>A".to_owned());
-    map.insert(('0', '4'), "^^<A".to_owned());
-    map.insert(('0', '5'), "^^A".to_owned());
-    map.insert(('0', '6'), "^^>A".to_owned());
-    map.insert(('0', '7'), "^^^<A".to_owned());
-    map.insert(('0', '8'), "^^^A".to_owned());
-    map.insert(('0', '9'), "^^^>A".to_owned());
-    // 1
-    map.insert(('1', 'A'), ">>vA".to_owned());
-    map.insert(('1', '0'), ">vA".to_owned());
-    map.insert(('1', '1'), "A".to_owned());
-    map.insert(('1', '2'), ">A".to_owned());
-    map.insert(('1', '3'), ">>A".to_owned());
-    map.insert(('1', '4'), "^A".to_owned());
-    map.insert(('1', '5'), ">^A".to_owned());
-    map.insert(('1', '6'), ">>^A".to_owned());
-    map.insert(('1', '7'), "^^A".to_owned());
-    map.insert(('1', '8'), ">^^A".to_owned());
-    map.insert(('1', '9'), ">>^^A".to_owned());
-    // 2
-    map.insert(('2', 'A'), ">vA".to_owned());
-    map.insert(('2', '0'), "vA".to_owned());
-    map.insert(('2', '1'), "<A".to_owned());
-    map.insert(('2', '2'), "A".to_owned());
-    map.insert(('2', '3'), ">A".to_owned());
-    map.insert(('2', '4'), "<^A".to_owned());
-    map.insert(('2', '5'), "^A".to_owned());
-    map.insert(('2', '6'), ">^A".to_owned());
-    map.insert(('2', '7'), "<^^A".to_owned());
-    map.insert(('2', '8'), "^^A".to_owned());
-    map.insert(('2', '9'), ">^^A".to_owned());
-    // 3
-    map.insert(('3', 'A'), "vA".to_owned());
-    map.insert(('3', '0'), "<vA".to_owned());
-    map.insert(('3', '1'), "<<A".to_owned());
-    map.insert(('3', '2'), "<A".to_owned());
-    map.insert(('3', '3'), "A".to_owned());
-    map.insert(('3', '4'), "<<^A".to_owned());
-    map.insert(('3', '5'), "<^A".to_owned());
-    map.insert(('3', '6'), "^A".to_owned());
-    map.insert(('3', '7'), "<<^^A".to_owned());
-    map.insert(('3', '8'), "<^^A".to_owned());
-    map.insert(('3', '9'), "^^A".to_owned());
-    // 4
-    map.insert(('4', 'A'), ">>vvA".to_owned());
-    map.insert(('4', '0'), ">vvA".to_owned());
-    map.insert(('4', '1'), "vA".to_owned());
-    map.insert(('4', '2'), ">vA".to_owned());
-    map.insert(('4', '3'), ">>vA".to_owned());
-    map.insert(('4', '4'), "A".to_owned());
-    map.insert(('4', '5'), ">A".to_owned());
-    map.insert(('4', '6'), ">>A".to_owned());
-    map.insert(('4', '7'), "^A".to_owned());
-    map.insert(('4', '8'), ">^A".to_owned());
-    map.insert(('4', '9'), ">>^A".to_owned());
-    // 5
-    map.insert(('5', 'A'), ">vvA".to_owned());
-    map.insert(('5', '0'), "vvA".to_owned());
-    map.insert(('5', '1'), "<vA".to_owned());
-    map.insert(('5', '2'), "vA".to_owned());
-    map.insert(('5', '3'), ">vA".to_owned());
-    map.insert(('5', '4'), "<A".to_owned());
-    map.insert(('5', '5'), "A".to_owned());
-    map.insert(('5', '6'), ">A".to_owned());
-    map.insert(('5', '7'), "<^A".to_owned());
-    map.insert(('5', '8'), "^A".to_owned());
-    map.insert(('5', '9'), ">^A".to_owned());
-    // 6
-    map.insert(('6', 'A'), "vvA".to_owned());
-    map.insert(('6', '0'), "<vvA".to_owned());
-    map.insert(('6', '1'), "<<vA".to_owned());
-    map.insert(('6', '2'), "<vA".to_owned());
-    map.insert(('6', '3'), "vA".to_owned());
-    map.insert(('6', '4'), "<<A".to_owned());
-    map.insert(('6', '5'), "<A".to_owned());
-    map.insert(('6', '6'), "A".to_owned());
-    map.insert(('6', '7'), "<<^A".to_owned());
-    map.insert(('6', '8'), "<^A".to_owned());
-    map.insert(('6', '9'), "^A".to_owned());
-    // 7
-    map.insert(('7', 'A'), ">>vvvA".to_owned());
-    map.insert(('7', '0'), ">vvvA".to_owned());
-    map.insert(('7', '1'), "vvA".to_owned());
-    map.insert(('7', '2'), ">vvA".to_owned());
-    map.insert(('7', '3'), ">>vvA".to_owned());
-    map.insert(('7', '4'), "vA".to_owned());
-    map.insert(('7', '5'), ">vA".to_owned());
-    map.insert(('7', '6'), ">>vA".to_owned());
-    map.insert(('7', '7'), "A".to_owned());
-    map.insert(('7', '8'), ">A".to_owned());
-    map.insert(('7', '9'), ">>A".to_owned());
-    // 8
-    map.insert(('8', 'A'), ">vvvA".to_owned());
-    map.insert(('8', '0'), "vvvA".to_owned());
-    map.insert(('8', '1'), "<vvA".to_owned());
-    map.insert(('8', '2'), "vvA".to_owned());
-    map.insert(('8', '3'), "<vvA".to_owned());
-    map.insert(('8', '4'), "<vA".to_owned());
-    map.insert(('8', '5'), "vA".to_owned());
-    map.insert(('8', '6'), ">vA".to_owned());
-    map.insert(('8', '7'), "<A".to_owned());
-    map.insert(('8', '8'), "A".to_owned());
-    map.insert(('8', '9'), ">A".to_owned());
-    // 9
-    map.insert(('9', 'A'), "vvvA".to_owned());
-    map.insert(('9', '0'), "<vvvA".to_owned());
-    map.insert(('9', '1'), "vv<<A".to_owned());
-    map.insert(('9', '2'), "vv<A".to_owned());
-    map.insert(('9', '3'), "vvA".to_owned());
-    map.insert(('9', '4'), "v<<A".to_owned());
-    map.insert(('9', '5'), "v<A".to_owned());
-    map.insert(('9', '6'), "vA".to_owned());
-    map.insert(('9', '7'), "<<A".to_owned());
-    map.insert(('9', '8'), "<A".to_owned());
-    map.insert(('9', '9'), "A".to_owned());
-
-    return map;
+struct KeypadChain {
+    numeric: Keypad,
+    directional: Keypad,
+    cache: HashMap<(char, char, usize), usize>,
 }
 
-fn arrow_directions() -> HashMap<(char, char), String> {
-    let mut map = HashMap::new();
+impl KeypadChain {
+    fn new() -> KeypadChain {
+        return KeypadChain {
+            numeric: Keypad::parse(&NUMERIC_LAYOUT),
+            directional: Keypad::parse(&DIRECTIONAL_LAYOUT),
+            cache: HashMap::new(),
+        };
+    }
 
-    // >
-    map.insert(('>', '>'), "A".to_owned());
-    map.insert(('>', 'v'), "<A".to_owned());
-    map.insert(('>', '<'), "<<A".to_owned());
-    map.insert(('>', '^'), "<^A".to_owned());
-    map.insert(('>', 'A'), "^A".to_owned());
-    // v
-    map.insert(('v', '>'), ">A".to_owned());
-    map.insert(('v', 'v'), "A".to_owned());
-    map.insert(('v', '<'), "<A".to_owned());
-    map.insert(('v', '^'), "^A".to_owned());
-    map.insert(('v', 'A'), ">^A".to_owned());
-    // <
-    map.insert(('<', '>'), ">>A".to_owned());
-    map.insert(('<', 'v'), ">A".to_owned());
-    map.insert(('<', '<'), "A".to_owned());
-    map.insert(('<', '^'), ">^A".to_owned());
-    map.insert(('<', 'A'), ">>^A".to_owned());
-    // ^
-    map.insert(('^', '>'), ">vA".to_owned());
-    map.insert(('^', 'v'), "vA".to_owned());
-    map.insert(('^', '<'), "v<A".to_owned());
-    map.insert(('^', '^'), "A".to_owned());
-    map.insert(('^', 'A'), ">A".to_owned());
-    // A
-    map.insert(('A', '>'), "vA".to_owned());
-    map.insert(('A', 'v'), "<vA".to_owned());
-    map.insert(('A', '<'), "v<<A".to_owned());
-    map.insert(('A', '^'), "<A".to_owned());
-    map.insert(('A', 'A'), "A".to_owned());
+    fn complexity(&mut self, code: &str, depth: usize) -> usize {
+        let numeric = code.trim_end_matches('A').parse::<usize>().unwrap();
+        return self.code_length(code, depth) * numeric;
+    }
 
-    return map;
-}
+    fn code_length(&mut self, code: &str, depth: usize) -> usize {
+        let mut length = 0;
+        let mut from = 'A';
+        for to in code.chars() {
+            length += self
+                .numeric
+                .moves(from, to)
+                .iter()
+                .map(|sequence| self.sequence_length(sequence, depth))
+                .min()
+                .unwrap();
+            from = to;
+        }
+        return length;
+    }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+    // Length of the presses needed on the outermost keypad to make the robot `depth`
+    // directional keypads away type `sequence`.
+    fn sequence_length(&mut self, sequence: &str, depth: usize) -> usize {
+        if depth == 0 {
+            return sequence.len();
+        }
+        let mut length = 0;
+        let mut from = 'A';
+        for to in sequence.chars() {
+            length += self.move_length(from, to, depth);
+            from = to;
+        }
+        return length;
+    }
+
+    fn move_length(&mut self, from: char, to: char, depth: usize) -> usize {
+        if let Some(length) = self.cache.get(&(from, to, depth)) {
+            return *length;
+        }
+        let length = self
+            .directional
+            .moves(from, to)
+            .iter()
+            .map(|sequence| self.sequence_length(sequence, depth - 1))
+            .min()
+            .unwrap();
+        self.cache.insert((from, to, depth), length);
+        return length;
+    }
 }
 
 #[cfg(test)]
@@ -258,21 +147,19 @@ mod tests {
         assert_eq!("126384", &actual);
     }
 
-    #[test]
-    fn first_robot_works() {
-        let actual = first_robot("029A", &directions());
-
-        assert_eq!("<A^A>^^AvvvA", actual);
-    }
-
-    #[test]
-    fn second_robot_works() {
-        let actual = second_robot("029A", &directions());
+    #[rstest]
+    #[case(0, "<A^A>^^AvvvA")]
+    #[case(1, "v<<A>>^A<A>AvA<^AA>A<vAAA>^A")]
+    #[case(
+        2,
+        "<vA<AA>>^AvAA<^A>Av<<A>>^AvA^A<vA>^Av<<A>^A>AAvA^Av<<A>A>^AAAvA<^A>A"
+    )]
+    fn code_length_works(#[case] depth: usize, #[case] expected: &str) {
+        let actual = KeypadChain::new().code_length("029A", depth);
 
-        assert_eq!("v<<A>>^A<A>AvA<^AA>A<vAAA>^A", actual);
+        assert_eq!(expected.len(), actual);
     }
 
-    // TODO: these might need to be looked at again
     #[rstest]
     #[case(
         "029A",
@@ -292,9 +179,9 @@ mod tests {
         "v<<A>>^AvA^A<vA<AA>>^AAvA<^A>AAvA^A<vA>^AA<A>Av<<A>A>^AAAvA<^A>A"
     )]
     fn third_robot_works(#[case] output: &str, #[case] expected: &str) {
-        let actual = third_robot(output, &directions());
+        let actual = KeypadChain::new().code_length(output, 2);
 
-        assert_eq!(expected, actual);
+        assert_eq!(expected.len(), actual);
     }
 
     #[rstest]
@@ -304,15 +191,26 @@ mod tests {
     #[case("456A", 29184)]
     #[case("379A", 24256)]
     fn complexity_works(#[case] output: &str, #[case] expected: usize) {
-        let actual = complexity(output, &directions());
+        let actual = KeypadChain::new().complexity(output, 2);
 
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn moves_avoid_the_gap() {
+        let numeric = Keypad::parse(&NUMERIC_LAYOUT);
+        let directional = Keypad::parse(&DIRECTIONAL_LAYOUT);
+
+        assert_eq!(vec!["^^^<<A"], numeric.moves('A', '7'));
+        assert_eq!(vec![">>vvvA"], numeric.moves('7', 'A'));
+        assert_eq!(vec!["v<<A"], directional.moves('A', '<'));
+        assert_eq!(vec![">^A", "^>A"], directional.moves('v', 'A'));
+    }
+
     #[test]
     fn part2_works() {
         let actual = create("test.txt").solve_part2();
 
-        assert_eq!("0", &actual);
+        assert_eq!("154115708116294", &actual);
     }
 }
@@ -1,6 +1,6 @@
 use std::ops::BitXor;
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use crate::create_advent_day;
 
@@ -47,8 +47,66 @@ fn prune(given: u64) -> u64 {
     return given % 16777216;
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+// Four changes in -9..=9 packed base 19 fit comfortably in a dense table.
+const CHANGE_BASE: usize = 19;
+const SEQUENCE_COUNT: usize = CHANGE_BASE * CHANGE_BASE * CHANGE_BASE * CHANGE_BASE;
+
+fn part2_with_input(input: &str) -> i64 {
+    let initial = input
+        .lines()
+        .map(|line| line.parse::<u64>().unwrap())
+        .collect::<Vec<_>>();
+    return best_bananas(&initial, 2000) as i64;
+}
+
+fn best_bananas(initial: &[u64], steps: usize) -> u32 {
+    let totals = initial
+        .par_iter()
+        .enumerate()
+        .fold(
+            || (vec![0u32; SEQUENCE_COUNT], vec![usize::MAX; SEQUENCE_COUNT]),
+            |(mut totals, mut first_seen), (buyer, first_value)| {
+                add_bananas(*first_value, steps, buyer, &mut totals, &mut first_seen);
+                (totals, first_seen)
+            },
+        )
+        .map(|(totals, _)| totals)
+        .reduce(
+            || vec![0u32; SEQUENCE_COUNT],
+            |mut first, second| {
+                first
+                    .iter_mut()
+                    .zip(second)
+                    .for_each(|(total, other)| *total += other);
+                first
+            },
+        );
+
+    return totals.into_iter().max().unwrap_or(0);
+}
+
+// The monkey sells the first time a sequence shows up, so each buyer only contributes
+// to a sequence once; `first_seen` remembers which buyer last claimed each key.
+fn add_bananas(
+    first_value: u64,
+    steps: usize,
+    buyer: usize,
+    totals: &mut [u32],
+    first_seen: &mut [usize],
+) {
+    let mut value = first_value;
+    let mut price = (value % 10) as usize;
+    let mut key = 0;
+    for step in 0..steps {
+        value = calculate(value);
+        let next_price = (value % 10) as usize;
+        key = (key * CHANGE_BASE + next_price + 9 - price) % SEQUENCE_COUNT;
+        price = next_price;
+        if step >= 3 && first_seen[key] != buyer {
+            first_seen[key] = buyer;
+            totals[key] += price as u32;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -97,8 +155,15 @@ mod tests {
 
     #[test]
     fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+        let actual = part2_with_input("1\n2\n3\n2024");
+
+        assert_eq!(23, actual);
+    }
+
+    #[test]
+    fn best_bananas_works() {
+        let actual = best_bananas(&[123], 9);
 
-        assert_eq!("0", &actual);
+        assert_eq!(6, actual);
     }
 }
@@ -1,61 +1,198 @@
-use std::collections::{HashMap, HashSet};
-
 use crate::create_advent_day;
 
 create_advent_day!("2024", "23");
 
 fn part1_with_input(input: &str) -> i64 {
-    let mut connections: HashMap<String, HashSet<String>> = HashMap::new();
-    input.lines().for_each(|line| {
-        let mut split = line.split("-");
-        let first = split.next().unwrap();
-        let second = split.next().unwrap();
-        connections
-            .entry(first.to_owned())
-            .or_default()
-            .insert(second.to_owned());
-        connections
-            .entry(second.to_owned())
-            .or_default()
-            .insert(first.to_owned());
-    });
-
-    let trios: HashSet<String> = connections
-        .iter()
-        .filter(|(key, _)| key.starts_with("t"))
-        .map(|(t_label, t_label_connections)| {
-            t_label_connections
-                .iter()
-                .map(|label| {
-                    connections
-                        .get(&label.clone())
-                        .unwrap()
-                        .iter()
-                        .filter_map(|third_label| {
-                            let contains_initial =
-                                connections.get(third_label).unwrap().contains(t_label);
-                            if contains_initial {
-                                let mut list =
-                                    vec![t_label.clone(), label.clone(), third_label.clone()];
-                                list.sort();
-                                Some(list.join(","))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<HashSet<_>>()
-                })
-                .flatten()
-                .collect::<HashSet<_>>()
-        })
-        .flatten()
-        .collect();
-
-    return trios.len() as i64;
+    let network = Network::parse(input);
+    return network.triangles_with_t() as i64;
+}
+
+fn part2_with_input(input: &str) -> String {
+    let network = Network::parse(input);
+    return network.password();
+}
+
+// Computer names are two lowercase letters, so every name maps to an id below 26 * 26.
+const NODE_COUNT: usize = 26 * 26;
+const WORDS: usize = NODE_COUNT.div_ceil(64);
+
+fn node_id(label: &str) -> u16 {
+    let bytes = label.as_bytes();
+    return (bytes[0] - b'a') as u16 * 26 + (bytes[1] - b'a') as u16;
+}
+
+fn node_label(id: u16) -> String {
+    let first = (b'a' + (id / 26) as u8) as char;
+    let second = (b'a' + (id % 26) as u8) as char;
+    return format!("{first}{second}");
+}
+
+fn starts_with_t(id: u16) -> bool {
+    return id / 26 == (b't' - b'a') as u16;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct NodeSet([u64; WORDS]);
+
+impl NodeSet {
+    fn empty() -> NodeSet {
+        return NodeSet([0; WORDS]);
+    }
+
+    fn insert(&mut self, id: u16) {
+        self.0[id as usize / 64] |= 1 << (id % 64);
+    }
+
+    fn remove(&mut self, id: u16) {
+        self.0[id as usize / 64] &= !(1 << (id % 64));
+    }
+
+    fn intersect(&self, other: &NodeSet) -> NodeSet {
+        let mut words = self.0;
+        words
+            .iter_mut()
+            .zip(other.0.iter())
+            .for_each(|(word, other)| *word &= other);
+        return NodeSet(words);
+    }
+
+    fn union(&self, other: &NodeSet) -> NodeSet {
+        let mut words = self.0;
+        words
+            .iter_mut()
+            .zip(other.0.iter())
+            .for_each(|(word, other)| *word |= other);
+        return NodeSet(words);
+    }
+
+    fn difference(&self, other: &NodeSet) -> NodeSet {
+        let mut words = self.0;
+        words
+            .iter_mut()
+            .zip(other.0.iter())
+            .for_each(|(word, other)| *word &= !other);
+        return NodeSet(words);
+    }
+
+    fn is_empty(&self) -> bool {
+        return self.0.iter().all(|word| *word == 0);
+    }
+
+    fn len(&self) -> usize {
+        return self.0.iter().map(|word| word.count_ones() as usize).sum();
+    }
+
+    fn ids(&self) -> Vec<u16> {
+        let mut ids = Vec::new();
+        for (index, word) in self.0.iter().enumerate() {
+            let mut word = *word;
+            while word != 0 {
+                ids.push((index * 64) as u16 + word.trailing_zeros() as u16);
+                word &= word - 1;
+            }
+        }
+        return ids;
+    }
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+struct Network {
+    nodes: NodeSet,
+    neighbors: Vec<NodeSet>,
+}
+
+impl Network {
+    fn parse(input: &str) -> Network {
+        let mut nodes = NodeSet::empty();
+        let mut neighbors = vec![NodeSet::empty(); NODE_COUNT];
+        input.lines().for_each(|line| {
+            let (first, second) = line.split_once('-').unwrap();
+            let first = node_id(first);
+            let second = node_id(second);
+            nodes.insert(first);
+            nodes.insert(second);
+            neighbors[first as usize].insert(second);
+            neighbors[second as usize].insert(first);
+        });
+        return Network { nodes, neighbors };
+    }
+
+    // Each triangle is counted once by only looking at neighbours with larger ids.
+    fn triangles_with_t(&self) -> usize {
+        let mut count = 0;
+        for first in self.nodes.ids() {
+            for second in self.neighbors[first as usize].ids() {
+                if second <= first {
+                    continue;
+                }
+                let shared =
+                    self.neighbors[first as usize].intersect(&self.neighbors[second as usize]);
+                count += shared
+                    .ids()
+                    .into_iter()
+                    .filter(|third| *third > second)
+                    .filter(|third| {
+                        starts_with_t(first) || starts_with_t(second) || starts_with_t(*third)
+                    })
+                    .count();
+            }
+        }
+        return count;
+    }
+
+    fn largest_clique(&self) -> NodeSet {
+        let mut best = NodeSet::empty();
+        self.bron_kerbosch(NodeSet::empty(), self.nodes, NodeSet::empty(), &mut best);
+        return best;
+    }
+
+    // Bron–Kerbosch with pivoting: any maximal clique must contain either the pivot or one of
+    // its non-neighbours, so only those candidates need branching on.
+    fn bron_kerbosch(
+        &self,
+        clique: NodeSet,
+        mut candidates: NodeSet,
+        mut excluded: NodeSet,
+        best: &mut NodeSet,
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > best.len() {
+                *best = clique;
+            }
+            return;
+        }
+        if clique.len() + candidates.len() <= best.len() {
+            return;
+        }
+        let pivot = candidates
+            .union(&excluded)
+            .ids()
+            .into_iter()
+            .max_by_key(|id| candidates.intersect(&self.neighbors[*id as usize]).len())
+            .unwrap();
+        for id in candidates.difference(&self.neighbors[pivot as usize]).ids() {
+            let neighbors = &self.neighbors[id as usize];
+            let mut next_clique = clique;
+            next_clique.insert(id);
+            self.bron_kerbosch(
+                next_clique,
+                candidates.intersect(neighbors),
+                excluded.intersect(neighbors),
+                best,
+            );
+            candidates.remove(id);
+            excluded.insert(id);
+        }
+    }
+
+    fn password(&self) -> String {
+        return self
+            .largest_clique()
+            .ids()
+            .into_iter()
+            .map(node_label)
+            .collect::<Vec<_>>()
+            .join(",");
+    }
 }
 
 #[cfg(test)]
@@ -73,6 +210,28 @@ mod tests {
     fn part2_works() {
         let actual = create("test.txt").solve_part2();
 
-        assert_eq!("0", &actual);
+        assert_eq!("co,de,ka,ta", &actual);
+    }
+
+    #[test]
+    fn node_ids_round_trip() {
+        assert_eq!(0, node_id("aa"));
+        assert_eq!(675, node_id("zz"));
+        assert_eq!("tc", node_label(node_id("tc")));
+        assert!(starts_with_t(node_id("tc")));
+        assert!(!starts_with_t(node_id("kh")));
+    }
+
+    #[test]
+    fn node_set_works() {
+        let mut set = NodeSet::empty();
+        set.insert(3);
+        set.insert(64);
+        set.insert(675);
+
+        assert_eq!(vec![3, 64, 675], set.ids());
+        set.remove(64);
+        assert_eq!(2, set.len());
+        assert_eq!(vec![3, 675], set.ids());
     }
 }
@@ -1,113 +1,420 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use crate::create_advent_day;
 
 create_advent_day!("2024", "24");
 
-fn part1_with_input(input: &str) -> i64 {
-    let mut split = input.split("\n\n");
-    let wires_input = split.next().unwrap();
-    let gates_input = split.next().unwrap();
-    let mut wires: HashMap<String, u8> = HashMap::new();
-    wires_input.lines().for_each(|line| {
-        let mut split = line.split_whitespace();
-        let label = split.next().unwrap().trim_matches(':').to_owned();
-        let value = split.next().unwrap().parse::<u8>().unwrap();
-        wires.insert(label, value);
-    });
-    let mut gates = gates_input
-        .lines()
-        .map(|line| {
-            let mut split = line.split_whitespace();
-            let input1 = split.next().unwrap().to_owned();
-            let operation = split.next().unwrap().chars().next().unwrap();
-            let input2 = split.next().unwrap().to_owned();
-            split.next(); // drop ->
-            let output_wire = split.next().unwrap().to_owned();
-            Gate {
-                input1,
-                input2,
-                operation,
-                output_wire,
-            }
-        })
-        .collect::<Vec<Gate>>();
+fn part1_with_input(input: &str) -> u64 {
+    let circuit = Circuit::parse(input);
 
-    loop {
-        let (solved, unsolved): (Vec<_>, Vec<_>) = gates
-            .into_iter()
-            .map(|gate| {
-                let solution = gate.solve(&wires);
-                (gate, solution)
-            })
-            .partition(|tuple| tuple.1.is_some());
-        solved.iter().for_each(|(gate, solution)| {
-            wires.insert(gate.output_wire.clone(), solution.unwrap());
-        });
-        gates = unsolved
-            .into_iter()
-            .map(|(gate, _)| gate)
-            .collect::<Vec<_>>();
-        if gates.len() == 0 {
-            break;
-        }
-    }
+    return circuit.evaluate(circuit.initial_x(), circuit.initial_y());
+}
 
-    let mut zeds = wires
+fn part2_with_input(input: &str) -> String {
+    let circuit = Circuit::parse(input);
+    let (_, pairs) = circuit
+        .repair()
+        .expect("no pairing of the suspect wires produces a working adder");
+    let mut wires = pairs
         .into_iter()
-        .filter(|(key, _)| key.starts_with('z'))
+        .flat_map(|(first, second)| [first, second])
         .collect::<Vec<_>>();
-    zeds.sort_by(|first, second| second.0.cmp(&first.0));
-    let value = zeds
-        .iter()
-        .map(|(_, value)| value.to_string())
-        .collect::<String>();
+    wires.sort();
+
+    return wires.join(",");
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Operation {
+    And,
+    Or,
+    Xor,
+}
+
+impl Operation {
+    fn parse(input: &str) -> Operation {
+        return match input {
+            "AND" => Operation::And,
+            "OR" => Operation::Or,
+            "XOR" => Operation::Xor,
+            _ => panic!("unknown operation {input}"),
+        };
+    }
 
-    return i64::from_str_radix(&value, 2).unwrap();
+    fn apply(&self, first: u8, second: u8) -> u8 {
+        return match self {
+            Operation::And => first & second,
+            Operation::Or => first | second,
+            Operation::Xor => first ^ second,
+        };
+    }
 }
 
+#[derive(Clone, Copy)]
 struct Gate {
-    input1: String,
-    input2: String,
-    operation: char,
-    output_wire: String,
+    input1: usize,
+    input2: usize,
+    operation: Operation,
+    output_wire: usize,
+}
+
+#[derive(Clone)]
+struct Circuit {
+    names: Vec<String>,
+    initial: HashMap<usize, u8>,
+    gates: Vec<Gate>,
+    x_wires: Vec<usize>,
+    y_wires: Vec<usize>,
+    z_wires: Vec<usize>,
 }
 
-impl Gate {
-    fn solve(&self, wires: &HashMap<String, u8>) -> Option<u8> {
-        let first = wires.get(&self.input1);
-        let second = wires.get(&self.input2);
-        if first.is_none() {
-            return None;
+impl Circuit {
+    fn parse(input: &str) -> Circuit {
+        let mut split = input.split("\n\n");
+        let wires_input = split.next().unwrap();
+        let gates_input = split.next().unwrap_or("");
+
+        let mut names: Vec<String> = Vec::new();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut intern = |label: &str| -> usize {
+            if let Some(id) = ids.get(label) {
+                return *id;
+            }
+            let id = names.len();
+            names.push(label.to_owned());
+            ids.insert(label.to_owned(), id);
+            return id;
+        };
+
+        let mut initial = HashMap::new();
+        wires_input.lines().for_each(|line| {
+            let mut split = line.split_whitespace();
+            let label = split.next().unwrap().trim_matches(':');
+            let value = split.next().unwrap().parse::<u8>().unwrap();
+            initial.insert(intern(label), value);
+        });
+        let gates = gates_input
+            .lines()
+            .map(|line| {
+                let mut split = line.split_whitespace();
+                let input1 = intern(split.next().unwrap());
+                let operation = Operation::parse(split.next().unwrap());
+                let input2 = intern(split.next().unwrap());
+                split.next(); // drop ->
+                let output_wire = intern(split.next().unwrap());
+                Gate {
+                    input1,
+                    input2,
+                    operation,
+                    output_wire,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let bus = |prefix: char| {
+            let mut wires = (0..names.len())
+                .filter(|id| names[*id].starts_with(prefix))
+                .collect::<Vec<_>>();
+            wires.sort_by(|first, second| names[*first].cmp(&names[*second]));
+            wires
+        };
+        let x_wires = bus('x');
+        let y_wires = bus('y');
+        let z_wires = bus('z');
+
+        let gates = topological_sort(&gates, names.len()).expect("circuit contains a loop");
+
+        return Circuit {
+            names,
+            initial,
+            gates,
+            x_wires,
+            y_wires,
+            z_wires,
+        };
+    }
+
+    fn initial_x(&self) -> u64 {
+        return self.read_initial(&self.x_wires);
+    }
+
+    fn initial_y(&self) -> u64 {
+        return self.read_initial(&self.y_wires);
+    }
+
+    fn read_initial(&self, wires: &[usize]) -> u64 {
+        return wires.iter().enumerate().fold(0, |acc, (bit, wire)| {
+            acc | ((*self.initial.get(wire).unwrap_or(&0) as u64) << bit)
+        });
+    }
+
+    fn evaluate(&self, x: u64, y: u64) -> u64 {
+        let mut values = vec![0u8; self.names.len()];
+        for (bit, wire) in self.x_wires.iter().enumerate() {
+            values[*wire] = ((x >> bit) & 1) as u8;
         }
-        if second.is_none() {
-            return None;
+        for (bit, wire) in self.y_wires.iter().enumerate() {
+            values[*wire] = ((y >> bit) & 1) as u8;
         }
-        let first = first.unwrap();
-        let second = second.unwrap();
-
-        let value = match self.operation {
-            'A' => *first == 1 && *second == 1,
-            'O' => *first == 1 || *second == 1,
-            'X' => *first != *second,
-            _ => todo!(),
-        };
-        if value {
-            Some(1)
+        for gate in self.gates.iter() {
+            values[gate.output_wire] = gate
+                .operation
+                .apply(values[gate.input1], values[gate.input2]);
+        }
+
+        return self
+            .z_wires
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (bit, wire)| acc | ((values[*wire] as u64) << bit));
+    }
+
+    fn wire(&self, label: &str) -> usize {
+        return self.names.iter().position(|name| name == label).unwrap();
+    }
+
+    fn is_input(&self, wire: usize) -> bool {
+        return self.x_wires.contains(&wire) || self.y_wires.contains(&wire);
+    }
+
+    fn is_first_bit(&self, gate: &Gate) -> bool {
+        return [gate.input1, gate.input2]
+            .iter()
+            .all(|wire| self.x_wires.first() == Some(wire) || self.y_wires.first() == Some(wire));
+    }
+
+    fn consumers(&self, wire: usize) -> Vec<Operation> {
+        return self
+            .gates
+            .iter()
+            .filter(|gate| gate.input1 == wire || gate.input2 == wire)
+            .map(|gate| gate.operation)
+            .collect();
+    }
+
+    // Each stage of a ripple-carry adder has the same shape:
+    //   x XOR y -> p, x AND y -> g, p XOR carry -> z, p AND carry -> t, g OR t -> carry
+    // so any gate that breaks one of these wiring rules has had its output swapped.
+    fn find_swapped_wires(&self) -> Vec<String> {
+        let last_z = self.z_wires.last().copied();
+        let mut suspects = self
+            .gates
+            .iter()
+            .filter(|gate| {
+                let output = gate.output_wire;
+                let from_inputs = self.is_input(gate.input1) && self.is_input(gate.input2);
+                if self.z_wires.contains(&output) {
+                    return if Some(output) == last_z {
+                        gate.operation != Operation::Or
+                    } else if from_inputs {
+                        !self.is_first_bit(gate)
+                    } else {
+                        gate.operation != Operation::Xor
+                    };
+                }
+                match gate.operation {
+                    Operation::Xor if !from_inputs => true,
+                    Operation::Xor => {
+                        !self.is_first_bit(gate)
+                            && !self.consumers(output).contains(&Operation::Xor)
+                    }
+                    Operation::And => {
+                        !self.is_first_bit(gate) && !self.consumers(output).contains(&Operation::Or)
+                    }
+                    Operation::Or => false,
+                }
+            })
+            .map(|gate| self.names[gate.output_wire].clone())
+            .collect::<Vec<_>>();
+        suspects.sort();
+
+        return suspects;
+    }
+
+    fn swap_outputs(&self, first: &str, second: &str) -> Option<Circuit> {
+        let first = self.wire(first);
+        let second = self.wire(second);
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| {
+                let mut gate = *gate;
+                if gate.output_wire == first {
+                    gate.output_wire = second;
+                } else if gate.output_wire == second {
+                    gate.output_wire = first;
+                }
+                gate
+            })
+            .collect::<Vec<_>>();
+        let gates = topological_sort(&gates, self.names.len())?;
+
+        return Some(Circuit {
+            gates,
+            ..self.clone()
+        });
+    }
+
+    fn repair(&self) -> Option<(Circuit, Vec<(String, String)>)> {
+        let suspects = self.find_swapped_wires();
+        return self.pair_up(&suspects, Vec::new());
+    }
+
+    fn pair_up(
+        &self,
+        remaining: &[String],
+        pairs: Vec<(String, String)>,
+    ) -> Option<(Circuit, Vec<(String, String)>)> {
+        if remaining.is_empty() {
+            return if self.adds_correctly(100) {
+                Some((self.clone(), pairs))
+            } else {
+                None
+            };
+        }
+        let first = &remaining[0];
+        for index in 1..remaining.len() {
+            let second = &remaining[index];
+            let swapped = match self.swap_outputs(first, second) {
+                Some(value) => value,
+                None => continue,
+            };
+            let rest = remaining
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != 0 && *other != index)
+                .map(|(_, name)| name.clone())
+                .collect::<Vec<_>>();
+            let mut pairs = pairs.clone();
+            pairs.push((first.clone(), second.clone()));
+            if let Some(result) = swapped.pair_up(&rest, pairs) {
+                return Some(result);
+            }
+        }
+
+        return None;
+    }
+
+    fn adds_correctly(&self, trials: usize) -> bool {
+        let bits = self.x_wires.len();
+        let mask = if bits >= 64 {
+            u64::MAX
         } else {
-            Some(0)
+            (1 << bits) - 1
+        };
+        let single_bits =
+            (0..bits).flat_map(|bit| [(1 << bit, 0), (0, 1 << bit), (1 << bit, 1 << bit)]);
+        let mut rng = XorShift(0x2024_1224);
+        let random = (0..trials).map(|_| (rng.next() & mask, rng.next() & mask));
+
+        return single_bits
+            .chain(random)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .all(|(x, y)| self.evaluate(x, y) == x + y);
+    }
+}
+
+fn topological_sort(gates: &[Gate], wire_count: usize) -> Option<Vec<Gate>> {
+    let mut producer: Vec<Option<usize>> = vec![None; wire_count];
+    for (index, gate) in gates.iter().enumerate() {
+        producer[gate.output_wire] = Some(index);
+    }
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); gates.len()];
+    let mut pending = vec![0; gates.len()];
+    for (index, gate) in gates.iter().enumerate() {
+        for input in [gate.input1, gate.input2] {
+            if let Some(source) = producer[input] {
+                dependents[source].push(index);
+                pending[index] += 1;
+            }
         }
     }
+
+    let mut ready = (0..gates.len())
+        .filter(|index| pending[*index] == 0)
+        .collect::<VecDeque<_>>();
+    let mut sorted = Vec::with_capacity(gates.len());
+    while let Some(index) = ready.pop_front() {
+        sorted.push(gates[index]);
+        for dependent in dependents[index].iter() {
+            pending[*dependent] -= 1;
+            if pending[*dependent] == 0 {
+                ready.push_back(*dependent);
+            }
+        }
+    }
+
+    return if sorted.len() == gates.len() {
+        Some(sorted)
+    } else {
+        None
+    };
 }
 
-fn part2_with_input(_input: &str) -> i64 {
-    return 0;
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return self.0;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn ripple_carry_adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut wires = (0..bits)
+            .map(|bit| format!("x{bit:02}: 0\ny{bit:02}: 0"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        wires.push_str("\n\n");
+        let carry = |bit: usize| {
+            if bit == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                format!("c{bit:02}")
+            }
+        };
+        let mut gates = vec![
+            ("x00 XOR y00".to_owned(), "z00".to_owned()),
+            ("x00 AND y00".to_owned(), carry(0)),
+        ];
+        for bit in 1..bits {
+            let previous = carry(bit - 1);
+            gates.push((format!("x{bit:02} XOR y{bit:02}"), format!("p{bit:02}")));
+            gates.push((format!("y{bit:02} AND x{bit:02}"), format!("g{bit:02}")));
+            gates.push((format!("{previous} XOR p{bit:02}"), format!("z{bit:02}")));
+            gates.push((format!("p{bit:02} AND {previous}"), format!("t{bit:02}")));
+            gates.push((format!("g{bit:02} OR t{bit:02}"), carry(bit)));
+        }
+        let gates = gates
+            .into_iter()
+            .map(|(operation, output)| {
+                let output = swaps
+                    .iter()
+                    .find_map(|(first, second)| {
+                        if output == *first {
+                            Some(second.to_string())
+                        } else if output == *second {
+                            Some(first.to_string())
+                        } else {
+                            None
+                        }
+                    })
+                    .unwrap_or(output);
+                format!("{operation} -> {output}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        return wires + &gates;
+    }
+
     #[test]
     fn part1_works() {
         let actual = create("test.txt").solve_part1();
@@ -117,8 +424,49 @@ mod tests {
 
     #[test]
     fn part2_works() {
-        let actual = create("test.txt").solve_part2();
+        let input = ripple_carry_adder(
+            45,
+            &[
+                ("z07", "t07"),
+                ("p12", "g12"),
+                ("z23", "c23"),
+                ("z31", "p35"),
+            ],
+        );
+
+        let actual = part2_with_input(&input);
+
+        assert_eq!("c23,g12,p12,p35,t07,z07,z23,z31", &actual);
+    }
+
+    #[test]
+    fn evaluate_adds() {
+        let circuit = Circuit::parse(&ripple_carry_adder(45, &[]));
+
+        assert_eq!(
+            123456789 + 987654321,
+            circuit.evaluate(123456789, 987654321)
+        );
+        assert!(circuit.find_swapped_wires().is_empty());
+        assert!(circuit.adds_correctly(100));
+    }
+
+    #[test]
+    fn repair_works() {
+        let circuit = Circuit::parse(&ripple_carry_adder(
+            45,
+            &[
+                ("z07", "t07"),
+                ("p12", "g12"),
+                ("z23", "c23"),
+                ("z31", "p35"),
+            ],
+        ));
+        assert!(!circuit.adds_correctly(100));
+
+        let (repaired, pairs) = circuit.repair().unwrap();
 
-        assert_eq!("0", &actual);
+        assert!(repaired.adds_correctly(1000));
+        assert_eq!(4, pairs.len());
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{cmp::Reverse, collections::BinaryHeap};
 
 use crate::create_advent_day;
 
@@ -6,15 +6,33 @@ create_advent_day!("2023", "17");
 
 fn part1_with_input(input: &str) -> u32 {
     let city_map = CityMap::parse(input);
-    let _shortest_path = city_map.shortest_path();
+    return city_map.shortest_path(&Crucible::NORMAL);
+}
+
+fn part2_with_input(input: &str) -> u32 {
+    let city_map = CityMap::parse(input);
+    return city_map.shortest_path(&Crucible::ULTRA);
+}
 
-    return input.lines().count() as u32;
+struct Crucible {
+    min_run: usize,
+    max_run: usize,
 }
 
-fn part2_with_input(input: &str) -> i32 {
-    return input.lines().count() as i32;
+impl Crucible {
+    const NORMAL: Crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+    };
+    const ULTRA: Crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+    };
 }
 
+// Headings are east, south, west, north so turning is +1 or +3 mod 4.
+const HEADINGS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
 struct CityMap {
     heat_loss: Vec<Vec<u32>>,
     width: usize,
@@ -41,74 +59,65 @@ impl CityMap {
         };
     }
 
-    // based on psuedo code here https://brilliant.org/wiki/dijkstras-short-path-finder/
-    fn shortest_path(&self) -> (u32, String) {
-        let mut solutions: HashMap<(usize, usize), (u32, String)> = HashMap::new();
-        let mut to_solve: Vec<(usize, usize)> = Vec::new();
-        // let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    fn state_index(
+        &self,
+        x: usize,
+        y: usize,
+        heading: usize,
+        run: usize,
+        crucible: &Crucible,
+    ) -> usize {
+        return ((y * self.width + x) * 4 + heading) * (crucible.max_run + 1) + run;
+    }
 
-        for i in 0..self.width {
-            for j in 0..self.height {
-                solutions.insert((i, j), (u32::MAX, "".to_string()));
-                to_solve.push((i, j));
-            }
+    // Dijkstra over (position, heading, run length). The crucible may keep going straight until
+    // it has moved max_run blocks, and may only turn or stop once it has moved min_run.
+    fn shortest_path(&self, crucible: &Crucible) -> u32 {
+        let mut best = vec![u32::MAX; self.width * self.height * 4 * (crucible.max_run + 1)];
+        let mut queue = BinaryHeap::new();
+        for heading in [0, 1] {
+            best[self.state_index(0, 0, heading, 0, crucible)] = 0;
+            queue.push(Reverse((0, 0, 0, heading, 0)));
         }
 
-        solutions.insert((0, 0), (0, "".to_string()));
-
-        while !to_solve.is_empty() {
-            let mut next_city_index = (0 as usize, 0 as usize);
-            let mut next_index = 0;
-            let mut next_shortest_distance = u32::MAX;
-            let mut next_shortest_path = "".to_string();
-            to_solve
-                .iter()
-                .enumerate()
-                .for_each(|(index, &city_index)| {
-                    // if visited.contains(&city_index) {
-                    //     return;
-                    // }
-                    if let Some(distance) = solutions.get(&city_index) {
-                        if distance.0 < next_shortest_distance {
-                            next_city_index = city_index;
-                            next_shortest_distance = distance.0;
-                            next_index = index;
-                            next_shortest_path = distance.1.clone();
-                        }
-                    }
-                });
-            to_solve.remove(next_index);
-            for neighbor in self.get_neighbors(&next_city_index) {
-                let heat_loss = self.heat_loss[neighbor.1][neighbor.0];
-                let alternative = next_shortest_distance + heat_loss;
-                let solution = solutions.get(&neighbor).unwrap();
-                if alternative < solution.0 {
-                    let next_path = format!("{}{}", next_shortest_path, heat_loss.to_string());
-                    solutions.insert(neighbor, (alternative, next_path));
+        while let Some(Reverse((loss, x, y, heading, run))) = queue.pop() {
+            if loss > best[self.state_index(x, y, heading, run, crucible)] {
+                continue;
+            }
+            if x == self.width - 1 && y == self.height - 1 && run >= crucible.min_run {
+                return loss;
+            }
+            let mut moves = Vec::new();
+            if run < crucible.max_run {
+                moves.push((heading, run + 1));
+            }
+            if run >= crucible.min_run || run == 0 {
+                moves.push(((heading + 1) % 4, 1));
+                moves.push(((heading + 3) % 4, 1));
+            }
+            for (next_heading, next_run) in moves {
+                let (dx, dy) = HEADINGS[next_heading];
+                let next_x = x as i32 + dx;
+                let next_y = y as i32 + dy;
+                if next_x < 0
+                    || next_y < 0
+                    || next_x >= self.width as i32
+                    || next_y >= self.height as i32
+                {
+                    continue;
+                }
+                let next_x = next_x as usize;
+                let next_y = next_y as usize;
+                let next_loss = loss + self.heat_loss[next_y][next_x];
+                let index = self.state_index(next_x, next_y, next_heading, next_run, crucible);
+                if next_loss < best[index] {
+                    best[index] = next_loss;
+                    queue.push(Reverse((next_loss, next_x, next_y, next_heading, next_run)));
                 }
             }
         }
 
-        let answer = solutions.get(&(self.width - 1, self.height - 1)).unwrap();
-
-        return (answer.0, answer.1.clone());
-    }
-
-    fn get_neighbors(&self, index: &(usize, usize)) -> Vec<(usize, usize)> {
-        let mut neighbors = Vec::new();
-        if index.0 != 0 {
-            neighbors.push((index.0 - 1, index.1));
-        }
-        if index.0 < self.width - 1 {
-            neighbors.push((index.0 + 1, index.1));
-        }
-        if index.1 != 0 {
-            neighbors.push((index.0, index.1 - 1));
-        }
-        if index.1 < self.height - 1 {
-            neighbors.push((index.0, index.1 + 1));
-        }
-        return neighbors;
+        panic!("no path to the factory");
     }
 }
 
@@ -126,14 +135,21 @@ mod test {
     fn part1_works() {
         let actual = create("test.txt").solve_part1();
 
-        assert_eq!(&actual, "13");
+        assert_eq!(&actual, "102");
     }
 
     #[test]
     fn part2_works() {
         let actual = create("test.txt").solve_part2();
 
-        assert_eq!(&actual, "13");
+        assert_eq!(&actual, "94");
+    }
+
+    #[test]
+    fn part2_ultra_crucible_must_run_before_stopping() {
+        let actual = create("test2.txt").solve_part2();
+
+        assert_eq!(&actual, "71");
     }
 
     #[rstest]
@@ -155,8 +171,7 @@ mod test {
     fn city_map_shortest_path_works() {
         let city_map = test_city_map();
 
-        let shortest_path = city_map.shortest_path();
-        assert_eq!(shortest_path.0, 78);
-        assert_eq!(&shortest_path.1, "413432311322342646373353")
+        assert_eq!(city_map.shortest_path(&Crucible::NORMAL), 102);
+        assert_eq!(city_map.shortest_path(&Crucible::ULTRA), 94);
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use crate::create_advent_day;
 
@@ -6,14 +6,14 @@ create_advent_day!("2023", "23");
 
 fn part1_with_input(input: &str) -> u32 {
     let forest = Forest::parse(input);
-    return forest.traverse();
+    let graph = ForestGraph::parse(&forest, false);
+    return graph.longest_hike().0;
 }
 
 fn part2_with_input(input: &str) -> u32 {
     let forest = Forest::parse(input);
-    let graph = ForestGraph::parse(&forest);
-    let mut seen_vertices = vec![vec![false; forest.width]; forest.height];
-    return graph.depth_first_search(&Point { x: 1, y: 0 }, &mut seen_vertices, 0);
+    let graph = ForestGraph::parse(&forest, true);
+    return graph.longest_hike().0;
 }
 
 struct Forest {
@@ -92,209 +92,241 @@ impl Forest {
         potential_neighbors.push(west_neighbor);
 
         return potential_neighbors
-            .iter()
-            .filter(|opt| opt.is_some())
-            .map(|opt| opt.clone().unwrap())
+            .into_iter()
+            .flatten()
             .collect::<Vec<_>>();
     }
-
-    fn get_unvisited_neighbors(&self, hike: &Hike) -> Vec<Point> {
-        let possible_neighbors = self.get_possible_neighbors(&hike.location, false);
-        return possible_neighbors
-            .iter()
-            .filter(|neighbor| !hike.visited.contains(neighbor))
-            .map(|point| point.clone())
-            .collect::<Vec<_>>();
-    }
-
-    fn traverse(&self) -> u32 {
-        let mut finished_hikes: Vec<Hike> = Vec::new();
-        let mut in_progress_hikes: Vec<Hike> = Vec::new();
-        let initial_hike = Self::initial_hike();
-        in_progress_hikes.push(initial_hike);
-        while !in_progress_hikes.is_empty() {
-            let mut num_hikes = in_progress_hikes.len();
-            let mut hike_index = 0;
-            let mut new_hikes: Vec<Hike> = Vec::new();
-            while hike_index < num_hikes {
-                let hike = in_progress_hikes.get_mut(hike_index).unwrap();
-                hike.visit_self();
-                let neighbors = self.get_unvisited_neighbors(hike);
-                if neighbors.len() == 0 {
-                    finished_hikes.push(hike.clone());
-                    in_progress_hikes.remove(hike_index);
-                    num_hikes -= 1;
-                    continue;
-                }
-                hike.move_to(&neighbors[0]);
-                for neighbor in neighbors[1..].iter() {
-                    let mut new_hike = hike.clone();
-                    new_hike.move_to(neighbor);
-                    new_hikes.push(new_hike);
-                }
-                hike_index += 1;
-            }
-            for hike in new_hikes {
-                in_progress_hikes.push(hike);
-            }
-        }
-        return finished_hikes
-            .iter()
-            .map(|hike| hike.visited.len())
-            .max()
-            .unwrap() as u32
-            - 1;
-    }
-
-    fn initial_hike() -> Hike {
-        let location = Point { x: 1, y: 1 };
-        let mut visited = HashSet::new();
-        visited.insert(Point { x: 1, y: 0 });
-
-        return Hike { location, visited };
-    }
-}
-
-#[derive(Clone)]
-struct Hike {
-    location: Point,
-    visited: HashSet<Point>,
-}
-
-impl Hike {
-    fn visit_self(&mut self) {
-        self.visited.insert(self.location.clone());
-    }
-
-    fn move_to(&mut self, location: &Point) {
-        self.location = location.clone();
-    }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 struct Point {
     x: i32,
     y: i32,
 }
 
-impl Point {
-    pub const MAX: usize = 140;
-
-    fn to_index(&self) -> usize {
-        return self.x as usize * Self::MAX + self.y as usize;
-    }
-}
-
-#[derive(PartialEq, Eq, Hash)]
 struct Edge {
-    destination: Point,
+    destination: usize,
     distance: u32,
+    // Every tile walked along the edge, excluding the junction it starts from.
+    _trail: Vec<Point>,
 }
 
 struct ForestGraph {
-    adjacency_list: Vec<HashSet<Edge>>,
-    end_point: Point,
+    junctions: Vec<Point>,
+    edges: Vec<Vec<Edge>>,
+    start: usize,
+    end: usize,
 }
 
 struct Trace {
-    starting_location: Point,
+    starting_junction: usize,
     current_location: Point,
     previous_location: Point,
+    trail: Vec<Point>,
 }
 
+// Enough partial routes to keep every rayon worker busy before switching to plain recursion.
+const PARALLEL_ROUTES: usize = 64;
+
 impl ForestGraph {
-    fn parse(forest: &Forest) -> ForestGraph {
-        let mut adjacency_list = Vec::with_capacity(Point::MAX * Point::MAX + 1);
-        for _ in 0..(Point::MAX * Point::MAX + 1) {
-            adjacency_list.push(HashSet::new());
-        }
+    // Collapses the forest into the junctions (tiles with three or more open neighbours, plus the
+    // start and end) and the corridors between them. With uphill_travel the slopes are treated as
+    // plain paths, otherwise corridors can only be walked in the direction the slopes allow.
+    fn parse(forest: &Forest, uphill_travel: bool) -> ForestGraph {
+        let start_point = Point { x: 1, y: 0 };
         let end_point = Point {
             x: forest.width as i32 - 2,
             y: forest.height as i32 - 1,
         };
+        let mut junction_ids = vec![vec![usize::MAX; forest.width]; forest.height];
+        let mut junctions = Vec::new();
+        for (y, row) in forest.map.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if *tile == b'#' {
+                    continue;
+                }
+                let point = Point {
+                    x: x as i32,
+                    y: y as i32,
+                };
+                let open_neighbors = forest.get_possible_neighbors(&point, true).len();
+                if open_neighbors >= 3 || point == start_point || point == end_point {
+                    junction_ids[y][x] = junctions.len();
+                    junctions.push(point);
+                }
+            }
+        }
+        let junction_at = |point: &Point| junction_ids[point.y as usize][point.x as usize];
+
+        let mut edges: Vec<Vec<Edge>> = junctions.iter().map(|_| Vec::new()).collect();
         let mut traces: Vec<Trace> = Vec::new();
-        traces.push(Trace {
-            starting_location: Point { x: 1, y: 0 },
-            current_location: Point { x: 1, y: 0 },
-            previous_location: Point { x: 0, y: 0 },
-        });
-        while !traces.is_empty() {
-            let mut trace = traces.pop().unwrap();
-            let mut num_paths = 1;
-            let mut steps_taken = 0;
-            while num_paths == 1 {
-                steps_taken += 1;
-                let possible_neighbors =
-                    forest.get_possible_neighbors(&trace.current_location, true);
-                let next_steps = possible_neighbors
-                    .iter()
-                    .filter(|&point| point != &trace.previous_location)
-                    .collect::<Vec<_>>();
-                num_paths = next_steps.len();
-                if num_paths == 0 {
-                    adjacency_list[trace.starting_location.to_index()].insert(Edge {
-                        destination: end_point.clone(),
-                        distance: steps_taken,
-                    });
-                    adjacency_list[end_point.to_index()].insert(Edge {
-                        destination: trace.starting_location.clone(),
-                        distance: steps_taken,
-                    });
-                } else if num_paths == 1 {
-                    trace.previous_location = trace.current_location;
-                    trace.current_location = next_steps[0].clone();
-                } else if num_paths > 1 {
-                    adjacency_list[trace.starting_location.to_index()].insert(Edge {
-                        destination: trace.current_location.clone(),
-                        distance: steps_taken,
-                    });
-                    let found_vertex = &mut adjacency_list[trace.current_location.to_index()];
-                    if found_vertex.is_empty() {
-                        for step in next_steps {
-                            traces.push(Trace {
-                                starting_location: trace.current_location.clone(),
-                                current_location: step.clone(),
-                                previous_location: trace.current_location.clone(),
-                            })
-                        }
-                    }
-                    found_vertex.insert(Edge {
-                        destination: trace.starting_location.clone(),
-                        distance: steps_taken,
+        for (id, junction) in junctions.iter().enumerate() {
+            for step in forest.get_possible_neighbors(junction, uphill_travel) {
+                traces.push(Trace {
+                    starting_junction: id,
+                    current_location: step,
+                    previous_location: *junction,
+                    trail: vec![step],
+                });
+            }
+        }
+        while let Some(mut trace) = traces.pop() {
+            loop {
+                let destination = junction_at(&trace.current_location);
+                if destination != usize::MAX {
+                    edges[trace.starting_junction].push(Edge {
+                        destination,
+                        distance: trace.trail.len() as u32,
+                        _trail: trace.trail,
                     });
+                    break;
+                }
+                let next_steps = forest
+                    .get_possible_neighbors(&trace.current_location, uphill_travel)
+                    .into_iter()
+                    .filter(|point| point != &trace.previous_location)
+                    .collect::<Vec<_>>();
+                if next_steps.is_empty() {
+                    break;
                 }
+                trace.previous_location = trace.current_location;
+                trace.current_location = next_steps[0];
+                trace.trail.push(next_steps[0]);
             }
         }
 
         return ForestGraph {
-            adjacency_list,
-            end_point,
+            start: junction_at(&start_point),
+            end: junction_at(&end_point),
+            junctions,
+            edges,
         };
     }
 
-    fn depth_first_search(
+    // Returns the longest hike along with the junctions it passes through, in order.
+    fn longest_hike(&self) -> (u32, Vec<usize>) {
+        assert!(
+            self.junctions.len() <= 64,
+            "too many junctions for a u64 mask"
+        );
+
+        // The end has only one way in, so once the hike reaches that junction it has to head
+        // straight for the exit: any other choice would cut itself off.
+        let last_junction = (0..self.junctions.len())
+            .filter(|id| {
+                self.edges[*id]
+                    .iter()
+                    .any(|edge| edge.destination == self.end)
+            })
+            .collect::<Vec<_>>();
+        let last_junction = if last_junction.len() == 1 {
+            Some(last_junction[0])
+        } else {
+            None
+        };
+
+        let mut best = (0, Vec::new());
+        let mut routes = vec![(1u64 << self.start, 0u32, vec![self.start])];
+        while routes.len() < PARALLEL_ROUTES {
+            let mut expanded = Vec::new();
+            let mut grew = false;
+            for (visited, distance, route) in routes {
+                let junction = *route.last().unwrap();
+                if junction == self.end {
+                    if distance > best.0 {
+                        best = (distance, route);
+                    }
+                    continue;
+                }
+                for edge in self.edges[junction].iter() {
+                    if visited & (1 << edge.destination) != 0 {
+                        continue;
+                    }
+                    let mut next_route = route.clone();
+                    next_route.push(edge.destination);
+                    expanded.push((
+                        visited | (1 << edge.destination),
+                        distance + edge.distance,
+                        next_route,
+                    ));
+                    grew = true;
+                }
+            }
+            routes = expanded;
+            if !grew {
+                break;
+            }
+        }
+
+        let parallel_best = routes
+            .into_par_iter()
+            .map(|(visited, distance, mut route)| {
+                let mut best = (0, Vec::new());
+                self.search(visited, distance, &mut route, last_junction, &mut best);
+                best
+            })
+            .max_by_key(|(distance, _)| *distance);
+        if let Some(parallel_best) = parallel_best {
+            if parallel_best.0 > best.0 {
+                best = parallel_best;
+            }
+        }
+        return best;
+    }
+
+    fn search(
         &self,
-        vertex: &Point,
-        seen_vertices: &mut Vec<Vec<bool>>,
+        visited: u64,
         distance: u32,
-    ) -> u32 {
-        if seen_vertices[vertex.y as usize][vertex.x as usize] {
-            return 0;
+        route: &mut Vec<usize>,
+        last_junction: Option<usize>,
+        best: &mut (u32, Vec<usize>),
+    ) {
+        let junction = *route.last().unwrap();
+        if junction == self.end {
+            if distance > best.0 {
+                *best = (distance, route.clone());
+            }
+            return;
         }
-        if vertex.eq(&self.end_point) {
-            // TODO: why is this off by one?
-            return distance - 1;
+        for edge in self.edges[junction].iter() {
+            if visited & (1 << edge.destination) != 0 {
+                continue;
+            }
+            if Some(junction) == last_junction && edge.destination != self.end {
+                continue;
+            }
+            route.push(edge.destination);
+            self.search(
+                visited | (1 << edge.destination),
+                distance + edge.distance,
+                route,
+                last_junction,
+                best,
+            );
+            route.pop();
         }
-        seen_vertices[vertex.y as usize][vertex.x as usize] = true;
-        let max = self.adjacency_list[vertex.to_index()]
-            .iter()
-            .map(|edge| {
-                self.depth_first_search(&edge.destination, seen_vertices, distance + edge.distance)
-            })
-            .max()
-            .unwrap();
-        seen_vertices[vertex.y as usize][vertex.x as usize] = false;
-        return max;
+    }
+
+    fn _render_route(&self, forest: &Forest, route: &[usize]) -> String {
+        let mut map = forest.map.clone();
+        let start = self.junctions[route[0]];
+        map[start.y as usize][start.x as usize] = b'O';
+        for pair in route.windows(2) {
+            let edge = self.edges[pair[0]]
+                .iter()
+                .filter(|edge| edge.destination == pair[1])
+                .max_by_key(|edge| edge.distance)
+                .unwrap();
+            for point in edge._trail.iter() {
+                map[point.y as usize][point.x as usize] = b'O';
+            }
+        }
+        return map
+            .into_iter()
+            .map(|row| String::from_utf8(row).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
     }
 }
 
@@ -337,8 +369,53 @@ mod test {
     fn forest_graph_parse_works() {
         let input = utils::read_file("2023/day23", "test.txt");
         let forest = Forest::parse(&input);
-        let _graph = ForestGraph::parse(&forest);
+        let graph = ForestGraph::parse(&forest, true);
 
-        assert_eq!(1, 1);
+        assert_eq!(9, graph.junctions.len());
+        assert_eq!(Point { x: 1, y: 0 }, graph.junctions[graph.start]);
+        assert_eq!(Point { x: 21, y: 22 }, graph.junctions[graph.end]);
+        assert_eq!(1, graph.edges[graph.start].len());
+        assert_eq!(15, graph.edges[graph.start][0].distance);
+    }
+
+    #[test]
+    fn render_route_works() {
+        let input = utils::read_file("2023/day23", "test.txt");
+        let forest = Forest::parse(&input);
+        let graph = ForestGraph::parse(&forest, false);
+        let (distance, route) = graph.longest_hike();
+
+        let rendered = graph._render_route(&forest, &route);
+
+        assert_eq!(
+            distance as usize + 1,
+            rendered.bytes().filter(|tile| *tile == b'O').count()
+        );
+        assert_eq!(
+            "#O#####################
+#OOOOOOO#########...###
+#######O#########.#.###
+###OOOOO#OOO>.###.#.###
+###O#####O#O#.###.#.###
+###OOOOO#O#O#.....#...#
+###v###O#O#O#########.#
+###...#O#O#OOOOOOO#...#
+#####.#O#O#######O#.###
+#.....#O#O#OOOOOOO#...#
+#.#####O#O#O#########v#
+#.#...#OOO#OOO###OOOOO#
+#.#.#v#######O###O###O#
+#...#.>.#...>OOO#O###O#
+#####v#.#.###v#O#O###O#
+#.....#...#...#O#O#OOO#
+#.#########.###O#O#O###
+#...###...#...#OOO#O###
+###.###.#.###v#####O###
+#...#...#.#.>.>.#.>O###
+#.###.###.#.###.#.#O###
+#.....###...###...#OOO#
+#####################O#",
+            rendered
+        );
     }
 }
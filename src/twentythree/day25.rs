@@ -1,32 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+
 use crate::create_advent_day;
 
 create_advent_day!("2023", "25");
 
 fn part1_with_input(input: &str) -> usize {
-    return input.len();
+    let wiring = Wiring::parse(input);
+    let cut = wiring.find_cut(3).expect("no three wire cut exists");
+
+    return cut.group_size * (wiring.names.len() - cut.group_size);
+}
+
+// There is no second puzzle on the last day, so report which wires were cut instead.
+fn part2_with_input(input: &str) -> String {
+    let wiring = Wiring::parse(input);
+    let cut = wiring.find_cut(3).expect("no three wire cut exists");
+
+    return cut
+        .wires
+        .iter()
+        .map(|(first, second)| format!("{first}/{second}"))
+        .collect::<Vec<_>>()
+        .join(",");
+}
+
+struct Wiring {
+    names: Vec<String>,
+    wires: Vec<(usize, usize)>,
+    // For each component, its neighbours paired with the index of the wire between them.
+    connections: Vec<Vec<(usize, usize)>>,
+}
+
+struct Cut {
+    group_size: usize,
+    wires: Vec<(String, String)>,
 }
 
-fn part2_with_input(input: &str) -> usize {
-    return input.len();
+impl Wiring {
+    fn parse(input: &str) -> Wiring {
+        let mut names: Vec<String> = Vec::new();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut wires = Vec::new();
+        let mut intern = |label: &str| -> usize {
+            if let Some(id) = ids.get(label) {
+                return *id;
+            }
+            let id = names.len();
+            names.push(label.to_owned());
+            ids.insert(label.to_owned(), id);
+            return id;
+        };
+        for line in input.lines() {
+            let (component, others) = line.split_once(": ").unwrap();
+            let component = intern(component);
+            for other in others.split_whitespace() {
+                wires.push((component, intern(other)));
+            }
+        }
+        let mut connections = vec![Vec::new(); names.len()];
+        for (index, (first, second)) in wires.iter().enumerate() {
+            connections[*first].push((*second, index));
+            connections[*second].push((*first, index));
+        }
+
+        return Wiring {
+            names,
+            wires,
+            connections,
+        };
+    }
+
+    // Treat every wire as a pipe of capacity one in both directions and push flow from the first
+    // component to each other one in turn. Whenever the max flow is exactly `size`, the components
+    // still reachable in the residual graph form one side of a minimum cut.
+    fn find_cut(&self, size: usize) -> Option<Cut> {
+        let source = 0;
+        for sink in 1..self.names.len() {
+            let mut flow = vec![0i8; self.wires.len()];
+            let mut total = 0;
+            while total <= size && self.augment(source, sink, &mut flow) {
+                total += 1;
+            }
+            if total != size {
+                continue;
+            }
+            let reachable = self.reachable(source, &flow);
+            let mut wires = self
+                .wires
+                .iter()
+                .filter(|(first, second)| reachable[*first] != reachable[*second])
+                .map(|(first, second)| {
+                    let mut pair = [self.names[*first].clone(), self.names[*second].clone()];
+                    pair.sort();
+                    let [first, second] = pair;
+                    (first, second)
+                })
+                .collect::<Vec<_>>();
+            wires.sort();
+            return Some(Cut {
+                group_size: reachable.iter().filter(|value| **value).count(),
+                wires,
+            });
+        }
+        return None;
+    }
+
+    fn residual(&self, from: usize, wire: usize, flow: &[i8]) -> i8 {
+        return if self.wires[wire].0 == from {
+            1 - flow[wire]
+        } else {
+            1 + flow[wire]
+        };
+    }
+
+    fn augment(&self, source: usize, sink: usize, flow: &mut [i8]) -> bool {
+        let mut came_from: Vec<Option<(usize, usize)>> = vec![None; self.names.len()];
+        let mut queue = VecDeque::from([source]);
+        while let Some(component) = queue.pop_front() {
+            if component == sink {
+                break;
+            }
+            for (other, wire) in self.connections[component].iter() {
+                if *other == source || came_from[*other].is_some() {
+                    continue;
+                }
+                if self.residual(component, *wire, flow) > 0 {
+                    came_from[*other] = Some((component, *wire));
+                    queue.push_back(*other);
+                }
+            }
+        }
+        if came_from[sink].is_none() {
+            return false;
+        }
+        let mut component = sink;
+        while let Some((previous, wire)) = came_from[component] {
+            flow[wire] += if self.wires[wire].0 == previous {
+                1
+            } else {
+                -1
+            };
+            component = previous;
+        }
+        return true;
+    }
+
+    fn reachable(&self, source: usize, flow: &[i8]) -> Vec<bool> {
+        let mut reachable = vec![false; self.names.len()];
+        reachable[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(component) = queue.pop_front() {
+            for (other, wire) in self.connections[component].iter() {
+                if !reachable[*other] && self.residual(component, *wire, flow) > 0 {
+                    reachable[*other] = true;
+                    queue.push_back(*other);
+                }
+            }
+        }
+        return reachable;
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
     #[test]
     fn part1_works() {
         let actual = create("test.txt").solve_part1();
 
-        assert_eq!(actual, "208");
+        assert_eq!(actual, "54");
     }
 
-    #[ignore]
     #[test]
     fn part2_works() {
         let actual = create("test.txt").solve_part2();
 
-        assert_eq!(actual, "208");
+        assert_eq!(actual, "bvb/cmg,hfx/pzl,jqt/nvd");
+    }
+
+    #[test]
+    fn find_cut_works() {
+        let wiring = Wiring::parse(&utils::read_file("2023/day25", "test.txt"));
+
+        let cut = wiring.find_cut(3).unwrap();
+
+        assert_eq!(
+            vec![
+                ("bvb".to_owned(), "cmg".to_owned()),
+                ("hfx".to_owned(), "pzl".to_owned()),
+                ("jqt".to_owned(), "nvd".to_owned()),
+            ],
+            cut.wires
+        );
+        assert!(cut.group_size == 6 || cut.group_size == 9);
     }
 }
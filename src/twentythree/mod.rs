@@ -45,7 +45,7 @@ pub fn add_twenty_three(map: &mut HashMap<String, AdventDay>) {
     map.insert("2023_14".to_owned(), day14::create("real.txt"));
     map.insert("2023_15".to_owned(), day15::create("real.txt"));
     map.insert("2023_16".to_owned(), day16::create("real.txt"));
-    map.insert("2023_17".to_owned(), day17::create("test.txt"));
+    map.insert("2023_17".to_owned(), day17::create("real.txt"));
     map.insert("2023_18".to_owned(), day18::create("real.txt"));
     map.insert("2023_19".to_owned(), day19::create("real.txt"));
     map.insert("2023_20".to_owned(), day20::create("real.txt"));
@@ -4,20 +4,20 @@ create_advent_day!("2022", "05");
 
 fn part1_with_input(input: &str) -> String {
     let mut puzzle = Puzzle::from(&input);
-    puzzle.resolve();
+    puzzle.resolve(&CRATE_MOVER_9000);
 
     return puzzle.get_code();
 }
 
 fn part2_with_input(input: &str) -> String {
     let mut puzzle = Puzzle::from(&input);
-    puzzle.resolve_9001();
+    puzzle.resolve(&CrateMover9001);
 
     return puzzle.get_code();
 }
 
 struct CargoBox {
-    id: char,
+    id: String,
 }
 
 struct Instruction {
@@ -26,6 +26,70 @@ struct Instruction {
     to_stack: i32,
 }
 
+trait Crane {
+    fn move_crates(&self, stacks: &mut [Vec<CargoBox>], instruction: &Instruction);
+}
+
+// Lifts the top crates of one stack in a single go, keeping their order.
+fn lift(stacks: &mut [Vec<CargoBox>], from_stack: usize, to_stack: usize, num_to_lift: usize) {
+    let from = &mut stacks[from_stack];
+    let lifted = from.split_off(from.len() - num_to_lift);
+    stacks[to_stack].extend(lifted);
+}
+
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn move_crates(&self, stacks: &mut [Vec<CargoBox>], instruction: &Instruction) {
+        lift(
+            stacks,
+            instruction.from_stack as usize - 1,
+            instruction.to_stack as usize - 1,
+            instruction.num_to_move as usize,
+        );
+    }
+}
+
+// Moves at most capacity crates per lift. The CrateMover 9000 can only lift one at a time.
+struct LimitedCrane {
+    capacity: usize,
+}
+
+const CRATE_MOVER_9000: LimitedCrane = LimitedCrane { capacity: 1 };
+
+impl Crane for LimitedCrane {
+    fn move_crates(&self, stacks: &mut [Vec<CargoBox>], instruction: &Instruction) {
+        let mut remaining = instruction.num_to_move as usize;
+        while remaining > 0 {
+            let num_to_lift = remaining.min(self.capacity);
+            lift(
+                stacks,
+                instruction.from_stack as usize - 1,
+                instruction.to_stack as usize - 1,
+                num_to_lift,
+            );
+            remaining -= num_to_lift;
+        }
+    }
+}
+
+// Start and end positions of each run of characters matching the predicate.
+fn spans(line: &str, is_part: impl Fn(char) -> bool) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, letter) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, is_part(letter)) {
+            (None, true) => start = Some(index),
+            (Some(begin), false) => {
+                spans.push((begin, index - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    return spans;
+}
+
 struct Puzzle {
     stacks: Vec<Vec<CargoBox>>,
     instructions: Vec<Instruction>,
@@ -49,24 +113,32 @@ impl Puzzle {
             }
         }
 
+        // Stacks are found from the numbers under them, and each crate goes to the stack whose
+        // number is centred closest to it, so neither the label widths nor the stack count need
+        // to be known up front.
+        let number_line = cargo_lines.pop().unwrap();
+        let stack_centers = spans(number_line, |letter| letter.is_ascii_digit())
+            .iter()
+            .map(|(start, end)| start + end)
+            .collect::<Vec<_>>();
         let mut stacks: Vec<Vec<CargoBox>> = Vec::new();
-        let num_stacks = (cargo_lines[0].len() + 1) / 4;
-        for _ in 0..num_stacks {
+        for _ in 0..stack_centers.len() {
             stacks.push(Vec::new());
         }
 
-        for line in cargo_lines.get(0..(cargo_lines.len() - 1)).unwrap() {
-            for i in 0..num_stacks {
-                let start_index = i * 4;
-                let potential_box = line.get(start_index..start_index + 3).unwrap();
-                if potential_box.starts_with('[') {
-                    stacks[i].insert(
-                        0,
-                        CargoBox {
-                            id: potential_box.chars().nth(1).unwrap(),
-                        },
-                    );
+        for line in cargo_lines.iter().rev() {
+            for (start, end) in spans(line, |letter| letter != ' ') {
+                let potential_box = &line[start..=end];
+                if !potential_box.starts_with('[') {
+                    continue;
                 }
+                let center = start + end;
+                let stack = (0..stack_centers.len())
+                    .min_by_key(|stack| stack_centers[*stack].abs_diff(center))
+                    .unwrap();
+                stacks[stack].push(CargoBox {
+                    id: potential_box[1..potential_box.len() - 1].to_owned(),
+                });
             }
         }
 
@@ -86,36 +158,15 @@ impl Puzzle {
         };
     }
 
-    fn resolve(&mut self) {
-        while self.instructions.len() > 0 {
-            self.apply_next_instruction();
-        }
-    }
-
-    fn apply_next_instruction(&mut self) {
-        let instruction = self.instructions.remove(0);
-        for _ in 0..instruction.num_to_move {
-            let cargo = self.stacks[instruction.from_stack as usize - 1]
-                .pop()
-                .unwrap();
-            self.stacks[instruction.to_stack as usize - 1].push(cargo);
-        }
-    }
-
-    fn resolve_9001(&mut self) {
+    fn resolve(&mut self, crane: &dyn Crane) {
         while self.instructions.len() > 0 {
-            self.apply_next_instruction_9001();
+            self.apply_next_instruction(crane);
         }
     }
 
-    fn apply_next_instruction_9001(&mut self) {
+    fn apply_next_instruction(&mut self, crane: &dyn Crane) {
         let instruction = self.instructions.remove(0);
-        let index_to_remove = self.stacks[instruction.from_stack as usize - 1].len()
-            - instruction.num_to_move as usize;
-        for _ in 0..instruction.num_to_move {
-            let cargo = self.stacks[instruction.from_stack as usize - 1].remove(index_to_remove);
-            self.stacks[instruction.to_stack as usize - 1].push(cargo);
-        }
+        crane.move_crates(&mut self.stacks, &instruction);
     }
 
     fn get_code(&self) -> String {
@@ -124,10 +175,63 @@ impl Puzzle {
             .iter()
             .map(|stack| stack.last())
             .map(Option::unwrap)
-            .map(|cargo| cargo.id)
+            .map(|cargo| cargo.id.as_str())
             .collect();
         return code;
     }
+
+    // Draws the stacks the way the puzzle input does, widening every column to fit the longest
+    // label.
+    fn _render(&self) -> String {
+        let label_width = self
+            .stacks
+            .iter()
+            .flatten()
+            .map(|cargo| cargo.id.len())
+            .max()
+            .unwrap_or(1);
+        let width = label_width + 2;
+        let height = self
+            .stacks
+            .iter()
+            .map(|stack| stack.len())
+            .max()
+            .unwrap_or(0);
+        let mut lines = (0..height)
+            .rev()
+            .map(|level| {
+                self.stacks
+                    .iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(cargo) => format!("[{:^label_width$}]", cargo.id),
+                        None => " ".repeat(width),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        lines.push(
+            (1..=self.stacks.len())
+                .map(|number| format!("{:^width$}", number))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        return lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    // Renders the stacks before any instruction and again after each one.
+    fn _animate(&mut self, crane: &dyn Crane) -> Vec<String> {
+        let mut frames = vec![self._render()];
+        while !self.instructions.is_empty() {
+            self.apply_next_instruction(crane);
+            frames.push(self._render());
+        }
+        return frames;
+    }
 }
 
 #[cfg(test)]
@@ -159,25 +263,46 @@ mod test {
 
         assert_eq!(actual.stacks.len(), 3);
         assert_eq!(actual.stacks[0].len(), 2);
-        assert_eq!(actual.stacks[0][0].id, 'Z');
-        assert_eq!(actual.stacks[0][1].id, 'N');
+        assert_eq!(actual.stacks[0][0].id, "Z");
+        assert_eq!(actual.stacks[0][1].id, "N");
         assert_eq!(actual.stacks[1].len(), 3);
-        assert_eq!(actual.stacks[1][0].id, 'M');
-        assert_eq!(actual.stacks[1][1].id, 'C');
-        assert_eq!(actual.stacks[1][2].id, 'D');
+        assert_eq!(actual.stacks[1][0].id, "M");
+        assert_eq!(actual.stacks[1][1].id, "C");
+        assert_eq!(actual.stacks[1][2].id, "D");
         assert_eq!(actual.stacks[2].len(), 1);
-        assert_eq!(actual.stacks[2][0].id, 'P');
+        assert_eq!(actual.stacks[2][0].id, "P");
         assert_eq!(actual.instructions.len(), 4);
         assert_eq!(actual.instructions[0].num_to_move, 1);
         assert_eq!(actual.instructions[0].from_stack, 2);
         assert_eq!(actual.instructions[0].to_stack, 1);
     }
 
+    #[test]
+    fn puzzle_from_handles_wide_labels_and_many_stacks() {
+        let input = "[AB]
+[CD] [E]                                          [XYZ]
+ 1    2    3    4    5    6    7    8    9    10    11
+
+move 2 from 1 to 10";
+        let mut puzzle = Puzzle::from(input);
+
+        assert_eq!(puzzle.stacks.len(), 11);
+        assert_eq!(puzzle.stacks[0].len(), 2);
+        assert_eq!(puzzle.stacks[0][1].id, "AB");
+        assert_eq!(puzzle.stacks[1][0].id, "E");
+        assert_eq!(puzzle.stacks[10][0].id, "XYZ");
+
+        puzzle.resolve(&CrateMover9001);
+
+        assert_eq!(puzzle.stacks[9].len(), 2);
+        assert_eq!(puzzle.stacks[9][1].id, "AB");
+    }
+
     #[test]
     fn puzzle_apply_next_instruction_works() {
         let mut puzzle = test_puzzle();
 
-        puzzle.apply_next_instruction();
+        puzzle.apply_next_instruction(&CRATE_MOVER_9000);
 
         assert_eq!(&puzzle.get_code(), "DCP");
     }
@@ -188,4 +313,41 @@ mod test {
 
         assert_eq!(&actual.get_code(), "NDP")
     }
+
+    #[test]
+    fn limited_crane_works() {
+        let input = "[A]\n[B]\n[C]\n[D]\n[E]\n 1   2\n\nmove 5 from 1 to 2";
+        let final_stack = |crane: &dyn Crane| {
+            let mut puzzle = Puzzle::from(input);
+            puzzle.resolve(crane);
+            puzzle.stacks[1]
+                .iter()
+                .map(|cargo| cargo.id.as_str())
+                .collect::<String>()
+        };
+
+        assert_eq!("ABCDE", final_stack(&CRATE_MOVER_9000));
+        assert_eq!("EDCBA", final_stack(&CrateMover9001));
+        assert_eq!("BADCE", final_stack(&LimitedCrane { capacity: 2 }));
+        assert_eq!("EDCBA", final_stack(&LimitedCrane { capacity: 5 }));
+    }
+
+    #[test]
+    fn animate_works() {
+        let mut puzzle = test_puzzle();
+
+        let frames = puzzle._animate(&CRATE_MOVER_9000);
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0], "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3");
+        assert_eq!(frames[1], "[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3");
+        assert_eq!(
+            frames[2],
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3"
+        );
+        assert_eq!(
+            frames[4],
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3"
+        );
+    }
 }
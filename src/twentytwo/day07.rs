@@ -1,154 +1,213 @@
+use std::ops::RangeBounds;
+
 use crate::create_advent_day;
 
 create_advent_day!("2022", "07");
 
-fn part1_with_input(input: &str) -> i32 {
-    let mut file_system = FileSystem::parse(&input);
-    file_system.root_dir.size();
-    return file_system.sum_small_dirs();
+const DISK_SIZE: u64 = 70_000_000;
+const NEEDED_SPACE: u64 = 30_000_000;
+
+fn part1_with_input(input: &str) -> u64 {
+    let file_system = FileSystem::parse(input);
+    return file_system
+        .dirs_sized(..=100_000)
+        .iter()
+        .map(|dir| file_system.dirs[*dir].size)
+        .sum();
 }
 
-fn part2_with_input(input: &str) -> i32 {
-    let mut file_system = FileSystem::parse(&input);
-    file_system.root_dir.size();
-    return file_system.smallest_dir_to_delete();
+fn part2_with_input(input: &str) -> u64 {
+    let file_system = FileSystem::parse(input);
+    let free_space = DISK_SIZE - file_system.dirs[ROOT].size;
+    let to_free = NEEDED_SPACE.saturating_sub(free_space);
+    return file_system
+        .dirs_sized(to_free..)
+        .iter()
+        .map(|dir| file_system.dirs[*dir].size)
+        .min()
+        .unwrap();
 }
 
+const ROOT: usize = 0;
+
+// Directories live in one arena and refer to each other by index, with the root at index 0.
 struct FileSystem {
-    root_dir: Dir,
+    dirs: Vec<Dir>,
 }
 
 impl FileSystem {
     fn parse(input: &str) -> FileSystem {
-        let mut curr_path: Vec<&str> = Vec::new();
-        let mut file_system: FileSystem = FileSystem {
-            root_dir: Dir::new("/"),
+        let mut file_system = FileSystem {
+            dirs: vec![Dir::new("/", None)],
         };
-        let mut curr_dir = &mut file_system.root_dir;
-        for line in input.lines().skip(1) {
-            if line.starts_with("$ cd ..") {
-                curr_path.pop();
-                curr_dir = file_system.get_dir(&path(&curr_path));
-            } else if line.starts_with("$ cd") {
-                curr_path.push(line.split("cd ").nth(1).unwrap());
-                curr_dir = file_system.get_dir(&path(&curr_path));
+        let mut curr_dir = ROOT;
+        for line in input.lines() {
+            if let Some(path) = line.strip_prefix("$ cd ") {
+                curr_dir = file_system.enter(curr_dir, path);
             } else if line.starts_with("$ ls") {
-            } else if line.starts_with("dir ") {
-                let mut split = line.split(" ");
-                let name = split.nth(1).unwrap();
-                curr_dir.dirs.push(Dir::new(name));
+            } else if let Some(name) = line.strip_prefix("dir ") {
+                file_system.child(curr_dir, name);
             } else {
                 let (size, name) = line.split_once(" ").unwrap();
-                let file = File {
-                    size: size.parse::<i32>().unwrap(),
-                    _name: name.to_owned(),
-                };
-                curr_dir.files.push(file);
+                let listed = file_system.dirs[curr_dir]
+                    .entries
+                    .iter()
+                    .any(|entry| matches!(entry, Entry::File(file) if file.name == name));
+                if !listed {
+                    file_system.dirs[curr_dir].entries.push(Entry::File(File {
+                        name: name.to_owned(),
+                        size: size.parse::<u64>().unwrap(),
+                    }));
+                }
+            }
+        }
+
+        // Children are always created after their parent, so walking the arena backwards
+        // finishes every directory before adding it to its parent.
+        for dir in (0..file_system.dirs.len()).rev() {
+            let files: u64 = file_system.dirs[dir]
+                .entries
+                .iter()
+                .map(|entry| match entry {
+                    Entry::File(file) => file.size,
+                    Entry::Dir(_) => 0,
+                })
+                .sum();
+            file_system.dirs[dir].size += files;
+            if let Some(parent) = file_system.dirs[dir].parent {
+                file_system.dirs[parent].size += file_system.dirs[dir].size;
             }
         }
 
         return file_system;
     }
 
-    fn get_dir(&mut self, full_path: &str) -> &mut Dir {
-        let path_parts = full_path[1..].split("/");
-        let mut dir = &mut self.root_dir;
-        'outer: for path in path_parts {
-            if path.len() == 0 {
-                continue;
-            }
-            let num_sub_dirs = dir.dirs.len();
-            for i in 0..num_sub_dirs {
-                if dir.dirs[i].name == path {
-                    dir = &mut dir.dirs[i];
-                    continue 'outer;
-                }
-            }
-            panic!("did not find {} in dir {}", path, dir.name);
+    // The named subdirectory, created when the transcript has not listed it yet.
+    fn child(&mut self, dir: usize, name: &str) -> usize {
+        if let Some(child) = self.find_child(dir, name) {
+            return child;
         }
+        let child = self.dirs.len();
+        self.dirs.push(Dir::new(name, Some(dir)));
+        self.dirs[dir].entries.push(Entry::Dir(child));
+        return child;
+    }
+
+    fn find_child(&self, dir: usize, name: &str) -> Option<usize> {
+        return self.dirs[dir].entries.iter().find_map(|entry| match entry {
+            Entry::Dir(child) if self.dirs[*child].name == name => Some(*child),
+            _ => None,
+        });
+    }
 
-        return dir;
+    fn step(&self, dir: usize, part: &str) -> Option<usize> {
+        return match part {
+            "" | "." => Some(dir),
+            ".." => Some(self.dirs[dir].parent.unwrap_or(ROOT)),
+            _ => self.find_child(dir, part),
+        };
     }
 
-    fn sum_small_dirs(self) -> i32 {
-        let mut dirs = Vec::new();
-        let mut add_my_children = Vec::new();
+    fn enter(&mut self, from: usize, path: &str) -> usize {
+        let start = if path.starts_with('/') { ROOT } else { from };
+        return path
+            .split('/')
+            .fold(start, |dir, part| match self.step(dir, part) {
+                Some(next) => next,
+                None => self.child(dir, part),
+            });
+    }
 
-        add_my_children.push(&self.root_dir);
-        while !add_my_children.is_empty() {
-            let current_dir = add_my_children.pop().unwrap();
-            for dir in &current_dir.dirs {
-                add_my_children.push(&dir);
-                dirs.push(dir);
-            }
+    // Looks up an absolute path, or one relative to the root.
+    fn _get_dir(&self, full_path: &str) -> Option<usize> {
+        return full_path
+            .split('/')
+            .try_fold(ROOT, |dir, part| self.step(dir, part));
+    }
+
+    fn _path(&self, dir: usize) -> String {
+        let mut names = Vec::new();
+        let mut curr_dir = dir;
+        while let Some(parent) = self.dirs[curr_dir].parent {
+            names.push(self.dirs[curr_dir].name.as_str());
+            curr_dir = parent;
         }
+        names.reverse();
+        return format!("/{}", names.join("/"));
+    }
 
-        return dirs
-            .iter()
-            .filter(|dir| dir.size <= 100_000)
-            .map(|dir| dir.size)
-            .sum();
+    // Every directory, the root included, whose total size falls in the range.
+    fn dirs_sized(&self, range: impl RangeBounds<u64>) -> Vec<usize> {
+        return (0..self.dirs.len())
+            .filter(|dir| range.contains(&self.dirs[*dir].size))
+            .collect();
     }
 
-    fn smallest_dir_to_delete(self) -> i32 {
-        // TODO: can we reuse the previous method instead of copy/paste?
-        let mut dirs = Vec::new();
-        let mut add_my_children = Vec::new();
+    // Lists each directory's total size and path, children before their parent like du does.
+    fn _du(&self) -> String {
+        let mut lines = Vec::new();
+        self._du_lines(ROOT, &mut lines);
+        return lines.join("\n");
+    }
 
-        add_my_children.push(&self.root_dir);
-        while !add_my_children.is_empty() {
-            let current_dir = add_my_children.pop().unwrap();
-            for dir in &current_dir.dirs {
-                add_my_children.push(&dir);
-                dirs.push(dir);
+    fn _du_lines(&self, dir: usize, lines: &mut Vec<String>) {
+        for entry in &self.dirs[dir].entries {
+            if let Entry::Dir(child) = entry {
+                self._du_lines(*child, lines);
             }
         }
+        lines.push(format!("{}\t{}", self.dirs[dir].size, self._path(dir)));
+    }
+
+    // Draws the tree the way the puzzle describes it, in the order entries were listed.
+    fn _tree(&self) -> String {
+        let mut lines = Vec::new();
+        self._tree_lines(ROOT, 0, &mut lines);
+        return lines.join("\n");
+    }
 
-        let current_free_space = 70_000_000 - self.root_dir.size;
-        return dirs
-            .iter()
-            .filter(|dir| dir.size + current_free_space >= 30_000_000)
-            .map(|dir| dir.size)
-            .min()
-            .unwrap();
+    fn _tree_lines(&self, dir: usize, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        lines.push(format!("{}- {} (dir)", indent, self.dirs[dir].name));
+        for entry in &self.dirs[dir].entries {
+            match entry {
+                Entry::Dir(child) => self._tree_lines(*child, depth + 1, lines),
+                Entry::File(file) => lines.push(format!(
+                    "{}  - {} (file, size={})",
+                    indent, file.name, file.size
+                )),
+            }
+        }
     }
 }
 
 struct Dir {
     name: String,
-    dirs: Vec<Dir>,
-    files: Vec<File>,
-    size: i32,
+    parent: Option<usize>,
+    entries: Vec<Entry>,
+    // Recursive size of everything below this directory, filled in once parsing is done.
+    size: u64,
 }
 
 impl Dir {
-    fn new(name: &str) -> Dir {
+    fn new(name: &str, parent: Option<usize>) -> Dir {
         return Dir {
             name: name.to_string(),
-            dirs: Vec::new(),
-            files: Vec::new(),
+            parent,
+            entries: Vec::new(),
             size: 0,
         };
     }
-
-    fn size(&mut self) -> i32 {
-        if self.size != 0 {
-            return self.size;
-        }
-        let file_size: i32 = self.files.iter().map(|file| file.size).sum();
-        let dir_size: i32 = self.dirs.iter_mut().map(|dir| dir.size()).sum();
-        self.size = file_size + dir_size;
-        return self.size;
-    }
 }
 
-struct File {
-    _name: String,
-    size: i32,
+enum Entry {
+    Dir(usize),
+    File(File),
 }
 
-fn path(path: &Vec<&str>) -> String {
-    return format!("/{}", path.join("/"));
+struct File {
+    name: String,
+    size: u64,
 }
 
 #[cfg(test)]
@@ -177,22 +236,104 @@ mod test {
     fn file_system_parse_works() {
         let actual = test_file_system();
 
-        assert_eq!(actual.root_dir.dirs.len(), 2);
-        assert_eq!(actual.root_dir.files.len(), 2);
+        assert_eq!(actual.dirs.len(), 4);
+        assert_eq!(actual.dirs[ROOT].entries.len(), 4);
+        assert_eq!(actual.dirs[ROOT].size, 48381165);
     }
 
     #[test]
     fn file_system_get_dir_works() {
-        let mut file_system = test_file_system();
+        let file_system = test_file_system();
 
-        let dir_a = file_system.get_dir("/a");
+        let dir_a = &file_system.dirs[file_system._get_dir("/a").unwrap()];
         assert_eq!(dir_a.name, "a");
-        assert_eq!(dir_a.dirs.len(), 1);
-        assert_eq!(dir_a.files.len(), 3);
+        assert_eq!(dir_a.entries.len(), 4);
+        assert_eq!(dir_a.size, 94853);
+
+        let dir_e = file_system._get_dir("/a/e").unwrap();
+        assert_eq!(file_system.dirs[dir_e].size, 584);
+        assert_eq!(file_system._path(dir_e), "/a/e");
+        assert_eq!(file_system._get_dir("/a/e/.."), file_system._get_dir("/a"));
+        assert_eq!(file_system._get_dir("/"), Some(ROOT));
+        assert_eq!(file_system._get_dir("/a/missing"), None);
+    }
+
+    #[test]
+    fn file_system_follows_any_cd() {
+        let file_system = FileSystem::parse(
+            "$ cd ..
+$ cd x/y
+$ ls
+10 f
+$ cd /
+$ cd /x/y
+$ ls
+10 f
+$ cd ../..
+$ cd z
+$ ls
+5 g",
+        );
+
+        assert_eq!(file_system.dirs[ROOT].size, 15);
+        assert_eq!(
+            file_system.dirs[file_system._get_dir("/x").unwrap()].size,
+            10
+        );
+        assert_eq!(file_system._path(file_system._get_dir("z").unwrap()), "/z");
+    }
+
+    #[test]
+    fn dirs_sized_works() {
+        let file_system = test_file_system();
+
+        let paths = |dirs: Vec<usize>| {
+            dirs.iter()
+                .map(|dir| file_system._path(*dir))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths(file_system.dirs_sized(500..100_000)),
+            vec!["/a", "/a/e"]
+        );
+        assert_eq!(
+            paths(file_system.dirs_sized(1_000_000..=24_933_642)),
+            vec!["/d"]
+        );
+        assert!(file_system.dirs_sized(..500).is_empty());
+    }
+
+    #[test]
+    fn du_works() {
+        let file_system = test_file_system();
+
+        assert_eq!(
+            file_system._du(),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/"
+        );
+    }
+
+    #[test]
+    fn tree_works() {
+        let file_system = test_file_system();
 
-        let dir_e = file_system.get_dir("/a/e");
-        assert_eq!(dir_e.name, "e");
-        assert!(dir_e.dirs.is_empty());
-        assert_eq!(dir_e.files.len(), 1);
+        assert_eq!(
+            file_system._tree(),
+            "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)"
+        );
     }
 }
@@ -1,249 +1,184 @@
+use std::collections::HashMap;
+
 use crate::create_advent_day;
 
 create_advent_day!("2022", "17");
 
-fn part1_with_input(input: &str) -> usize {
+fn part1_with_input(input: &str) -> u64 {
     let mut chamber = Chamber::new(input);
-    chamber.simulate(2022);
-    return chamber.highest_rock();
+    return chamber.height_after(2022);
 }
 
 fn part2_with_input(input: &str) -> u64 {
-    let _chamber = Chamber::new(input);
-    // chamber.simulate(1000000000000);
-    return 1_514_285_714_288;
+    let mut chamber = Chamber::new(input);
+    return chamber.height_after(1_000_000_000_000);
+}
+
+// Each row is a 7 bit mask with bit 6 as the leftmost column. Pieces are listed bottom row
+// first, already shifted so their left edge sits two units from the wall.
+const PIECES: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
+
+const LEFT_WALL: u8 = 0b1000000;
+const RIGHT_WALL: u8 = 0b0000001;
+
+// How far below the top of the tower the state key looks. Anything deeper than this is
+// assumed to be sealed off from falling rocks.
+const PROFILE_DEPTH: usize = 64;
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+struct StateKey {
+    profile: [u8; 7],
+    jet_index: usize,
+    piece_index: usize,
 }
 
 struct Chamber {
-    chamber: Vec<[u8; 7]>,
-    wind_stream: Box<dyn Iterator<Item = u8>>,
-    next_piece: Box<dyn Iterator<Item = PieceType>>,
+    rows: Vec<u8>,
+    height: usize,
+    jets: Vec<u8>,
+    jet_index: usize,
+    piece_index: usize,
 }
 
 impl Chamber {
     fn new(input: &str) -> Self {
-        let wind_stream = Box::new(input.bytes().collect::<Vec<_>>().into_iter().cycle());
-        let next_piece = Box::new(
-            vec![
-                PieceType::HORIZONTAL,
-                PieceType::PLUS,
-                PieceType::INVERTED,
-                PieceType::VERTICAL,
-                PieceType::SQUARE,
-            ]
-            .into_iter()
-            .cycle(),
-        );
-
         return Self {
-            chamber: Vec::new(),
-            wind_stream,
-            next_piece,
+            rows: Vec::new(),
+            height: 0,
+            jets: input.trim().bytes().collect(),
+            jet_index: 0,
+            piece_index: 0,
         };
     }
 
-    fn highest_rock(&self) -> usize {
-        let current_height = self.chamber.len();
-        for index in (0..current_height).rev() {
-            for char in self.chamber[index] {
-                if char != b'.' {
-                    return index + 1;
-                }
-            }
-        }
-        return 0;
+    fn jet_index(&self) -> usize {
+        return self.jet_index;
     }
 
-    fn grow_chamber(&mut self, to: usize) {
-        let current_height = self.chamber.len();
-        for _ in current_height..to {
-            self.chamber.push([b'.'; 7]);
-        }
+    fn piece_index(&self) -> usize {
+        return self.piece_index;
     }
 
-    fn blow_left(&self, piece: &mut Piece) {
-        let can_move_left = piece
-            .points
+    fn collides(&self, piece: &[u8], bottom: usize) -> bool {
+        return piece
             .iter()
-            .map(|point| point.x > 0 && self.chamber[point.y][point.x - 1] == b'.')
-            .fold(true, |acc, ele| acc && ele);
-        if can_move_left {
-            piece.points.iter_mut().for_each(|point| point.x -= 1);
+            .enumerate()
+            .any(|(offset, row)| self.rows[bottom + offset] & row != 0);
+    }
+
+    fn drop_rock(&mut self) {
+        let mut piece = PIECES[self.piece_index].to_vec();
+        self.piece_index = (self.piece_index + 1) % PIECES.len();
+        let mut bottom = self.height + 3;
+        self.rows.resize(bottom + piece.len(), 0);
+
+        loop {
+            let jet = self.jets[self.jet_index];
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
+            let pushed = match jet {
+                b'<' if piece.iter().all(|row| row & LEFT_WALL == 0) => {
+                    piece.iter().map(|row| row << 1).collect::<Vec<_>>()
+                }
+                b'>' if piece.iter().all(|row| row & RIGHT_WALL == 0) => {
+                    piece.iter().map(|row| row >> 1).collect::<Vec<_>>()
+                }
+                b'<' | b'>' => piece.clone(),
+                _ => unreachable!("unexpected wind direction"),
+            };
+            if !self.collides(&pushed, bottom) {
+                piece = pushed;
+            }
+            if bottom == 0 || self.collides(&piece, bottom - 1) {
+                break;
+            }
+            bottom -= 1;
         }
-    }
 
-    fn blow_right(&self, piece: &mut Piece) {
-        let can_move_right = piece
-            .points
-            .iter()
-            .map(|point| point.x < 6 && self.chamber[point.y][point.x + 1] == b'.')
-            .fold(true, |acc, ele| acc && ele);
-        if can_move_right {
-            piece.points.iter_mut().for_each(|point| point.x += 1);
+        for (offset, row) in piece.iter().enumerate() {
+            self.rows[bottom + offset] |= row;
         }
-    }
-
-    fn fall(&self, piece: &mut Piece) -> bool {
-        let can_fall = piece
-            .points
-            .iter()
-            .map(|point| point.y > 0 && self.chamber[point.y - 1][point.x] == b'.')
-            .fold(true, |acc, ele| acc && ele);
-        if can_fall {
-            piece.points.iter_mut().for_each(|point| point.y -= 1);
+        self.height = self.height.max(bottom + piece.len());
+    }
+
+    // Depth of the highest rock in each column, measured down from the top of the tower.
+    fn top_profile(&self) -> [u8; 7] {
+        let mut profile = [PROFILE_DEPTH as u8; 7];
+        for (column, depth) in profile.iter_mut().enumerate() {
+            let mask = LEFT_WALL >> column;
+            for offset in 0..PROFILE_DEPTH.min(self.height) {
+                if self.rows[self.height - 1 - offset] & mask != 0 {
+                    *depth = offset as u8;
+                    break;
+                }
+            }
         }
-
-        return can_fall;
+        return profile;
     }
 
-    fn simulate(&mut self, num_pieces: usize) {
-        let mut num_landed = 0;
-        // let mut last_highest = 0;
-        while num_landed < num_pieces {
-            // println!("after {} pieces, tower grew {}", num_landed, self.highest_rock() - last_highest);
-            // last_highest = self.highest_rock();
-            let lower_left = Point {
-                x: 2,
-                y: self.highest_rock() + 3,
-            };
-            let mut piece = Piece::new(&self.next_piece.next().unwrap(), &lower_left);
-            self.grow_chamber(piece.highest_y() + 1);
-            let mut has_landed = false;
+    fn state_key(&self) -> StateKey {
+        return StateKey {
+            profile: self.top_profile(),
+            jet_index: self.jet_index(),
+            piece_index: self.piece_index(),
+        };
+    }
 
-            while !has_landed {
-                let next_wind = self.wind_stream.next().unwrap();
-                match next_wind {
-                    b'<' => self.blow_left(&mut piece),
-                    b'>' => self.blow_right(&mut piece),
-                    _ => unreachable!("unexpected wind direction"),
-                };
-                let fell = self.fall(&mut piece);
-                if !fell {
-                    has_landed = true;
-                    piece.points.iter().for_each(|point| {
-                        self.chamber[point.y][point.x] = b'#';
-                    })
-                }
+    // Drops rocks until the (profile, jet, piece) state repeats, then skips ahead over as many
+    // whole cycles as fit before simulating the remainder.
+    fn height_after(&mut self, rocks: u64) -> u64 {
+        let mut seen: HashMap<StateKey, (u64, u64)> = HashMap::new();
+        let mut dropped = 0;
+        let mut skipped_height = 0;
+        while dropped < rocks {
+            self.drop_rock();
+            dropped += 1;
+            if skipped_height != 0 {
+                continue;
+            }
+            let key = self.state_key();
+            if let Some((previous_dropped, previous_height)) = seen.get(&key) {
+                let cycle_length = dropped - previous_dropped;
+                let cycle_height = self.height as u64 - previous_height;
+                let cycles = (rocks - dropped) / cycle_length;
+                dropped += cycles * cycle_length;
+                skipped_height = cycles * cycle_height;
+            } else {
+                seen.insert(key, (dropped, self.height as u64));
             }
-
-            num_landed += 1;
         }
+        return self.height as u64 + skipped_height;
     }
 
     fn _debug(&self) -> String {
-        return self
-            .chamber
+        return self.rows[..self.height]
             .iter()
-            .map(|arr| String::from_utf8(arr.to_vec()).unwrap())
             .rev()
+            .map(|row| {
+                (0..7)
+                    .map(|column| {
+                        if row & (LEFT_WALL >> column) != 0 {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
             .collect::<Vec<_>>()
             .join("\n");
     }
 }
 
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-#[derive(Clone)]
-enum PieceType {
-    HORIZONTAL,
-    PLUS,
-    INVERTED,
-    VERTICAL,
-    SQUARE,
-}
-
-struct Piece {
-    points: Vec<Point>,
-}
-
-impl Piece {
-    fn new(piece_type: &PieceType, lower_left: &Point) -> Self {
-        return match piece_type {
-            PieceType::HORIZONTAL => Self::new_horizontal(lower_left),
-            PieceType::PLUS => Self::new_plus(lower_left),
-            PieceType::INVERTED => Self::new_inverted(lower_left),
-            PieceType::VERTICAL => Self::new_vertical(lower_left),
-            PieceType::SQUARE => Self::new_square(lower_left),
-        };
-    }
-
-    fn new_horizontal(lower_left: &Point) -> Self {
-        let mut points = Vec::new();
-        for x in 0..4 {
-            points.push(Point {
-                x: lower_left.x + x,
-                y: lower_left.y,
-            });
-        }
-        return Self { points };
-    }
-    fn new_plus(lower_left: &Point) -> Self {
-        let mut points = Vec::new();
-        for x in 0..3 {
-            points.push(Point {
-                x: lower_left.x + x,
-                y: lower_left.y + 1,
-            });
-        }
-        points.push(Point {
-            x: lower_left.x + 1,
-            y: lower_left.y,
-        });
-        points.push(Point {
-            x: lower_left.x + 1,
-            y: lower_left.y + 2,
-        });
-        return Self { points };
-    }
-    fn new_inverted(lower_left: &Point) -> Self {
-        let mut points = Vec::new();
-        for x in 0..3 {
-            points.push(Point {
-                x: lower_left.x + x,
-                y: lower_left.y,
-            });
-        }
-        for y in 1..3 {
-            points.push(Point {
-                x: lower_left.x + 2,
-                y: lower_left.y + y,
-            });
-        }
-        return Self { points };
-    }
-    fn new_vertical(lower_left: &Point) -> Self {
-        let mut points = Vec::new();
-        for y in 0..4 {
-            points.push(Point {
-                x: lower_left.x,
-                y: lower_left.y + y,
-            });
-        }
-        return Self { points };
-    }
-    fn new_square(lower_left: &Point) -> Self {
-        let mut points = Vec::new();
-        for x in 0..2 {
-            for y in 0..2 {
-                points.push(Point {
-                    x: lower_left.x + x,
-                    y: lower_left.y + y,
-                })
-            }
-        }
-        return Self { points };
-    }
-
-    fn highest_y(&self) -> usize {
-        return self.points.iter().map(|point| point.y).max().unwrap();
-    }
-}
-
 #[cfg(test)]
 mod test {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -260,16 +195,35 @@ mod test {
         assert_eq!(actual, "1514285714288");
     }
 
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 4)]
+    #[case(3, 6)]
+    #[case(10, 17)]
+    #[case(2022, 3068)]
+    fn height_after_works(#[case] rocks: u64, #[case] expected: u64) {
+        let mut chamber = Chamber::new(&utils::read_file("2022/day17", "test.txt"));
+
+        assert_eq!(expected, chamber.height_after(rocks));
+    }
+
     #[test]
-    fn cycle_works_as_expected() {
-        let vec = vec!['<', '>'];
-        let mut repeated = vec.iter().cycle();
-        let first_take = repeated.next().unwrap();
-        let second_take = repeated.next().unwrap();
-        let third_take = repeated.next().unwrap();
+    fn drop_rock_works() {
+        let mut chamber = Chamber::new(&utils::read_file("2022/day17", "test.txt"));
+        for _ in 0..3 {
+            chamber.drop_rock();
+        }
 
-        assert_eq!(*first_take, '<');
-        assert_eq!(*second_take, '>');
-        assert_eq!(*third_take, '<');
+        assert_eq!(
+            "..#....
+..#....
+####...
+..###..
+...#...
+..####.",
+            chamber._debug()
+        );
+        assert_eq!(3, chamber.piece_index());
+        assert_eq!(13, chamber.jet_index());
     }
 }
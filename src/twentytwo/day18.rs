@@ -1,48 +1,148 @@
 use std::collections::HashSet;
 
 use crate::create_advent_day;
+use crate::utils::{bounding_box, face_neighbors, in_box, Voxel};
 
 create_advent_day!("2022", "18");
 
 fn part1_with_input(input: &str) -> usize {
-    let cubes = input
-        .lines()
-        .map(|line| {
-            let split = line
-                .split(",")
-                .map(|str| str.parse::<i32>().unwrap())
-                .collect::<Vec<_>>();
-            (split[0], split[1], split[2])
-        })
-        .collect::<HashSet<_>>();
-    let borders = cubes
-        .iter()
-        .map(|cube| {
-            vec![
-                (cube.0 - 1, cube.1, cube.2),
-                (cube.0 + 1, cube.1, cube.2),
-                (cube.0, cube.1 - 1, cube.2),
-                (cube.0, cube.1 + 1, cube.2),
-                (cube.0, cube.1, cube.2 - 1),
-                (cube.0, cube.1, cube.2 + 1),
-            ]
-        })
-        .flatten()
-        .collect::<Vec<_>>();
-    return borders
-        .iter()
-        .filter(|border| !cubes.contains(border))
-        .count();
+    let droplet = Droplet::parse(input);
+    return droplet.surface_area();
 }
 
-fn part2_with_input(_input: &str) -> u32 {
-    return 0;
+fn part2_with_input(input: &str) -> usize {
+    let droplet = Droplet::parse(input);
+    return droplet.exterior_surface_area();
+}
+
+struct Droplet {
+    cubes: HashSet<Voxel>,
+    // The droplet's bounding box grown by one, so steam can reach around every side.
+    _bounds: (Voxel, Voxel),
+    steam: HashSet<Voxel>,
+}
+
+impl Droplet {
+    fn parse(input: &str) -> Self {
+        let cubes = input
+            .lines()
+            .map(|line| {
+                let split = line
+                    .split(",")
+                    .map(|str| str.parse::<i32>().unwrap())
+                    .collect::<Vec<_>>();
+                (split[0], split[1], split[2])
+            })
+            .collect::<HashSet<_>>();
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = bounding_box(&cubes).unwrap();
+        let bounds = (
+            (min_x - 1, min_y - 1, min_z - 1),
+            (max_x + 1, max_y + 1, max_z + 1),
+        );
+        let steam = Self::flood(&cubes, bounds, bounds.0);
+        return Self {
+            cubes,
+            _bounds: bounds,
+            steam,
+        };
+    }
+
+    // Every empty voxel inside bounds that can be reached from start without passing through
+    // the droplet.
+    fn flood(cubes: &HashSet<Voxel>, bounds: (Voxel, Voxel), start: Voxel) -> HashSet<Voxel> {
+        let mut reached = HashSet::from([start]);
+        let mut to_visit = vec![start];
+        while let Some(voxel) = to_visit.pop() {
+            for neighbor in face_neighbors(voxel) {
+                if in_box(neighbor, bounds)
+                    && !cubes.contains(&neighbor)
+                    && reached.insert(neighbor)
+                {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+        return reached;
+    }
+
+    fn surface_area(&self) -> usize {
+        return self
+            .cubes
+            .iter()
+            .flat_map(|cube| face_neighbors(*cube))
+            .filter(|neighbor| !self.cubes.contains(neighbor))
+            .count();
+    }
+
+    // Faces count only when steam from outside can touch them. Anything past the grown bounding
+    // box is steam as well, but no cube is adjacent to it.
+    fn exterior_surface_area(&self) -> usize {
+        return self
+            .cubes
+            .iter()
+            .flat_map(|cube| face_neighbors(*cube))
+            .filter(|neighbor| self.steam.contains(neighbor))
+            .count();
+    }
+
+    // The volume of each connected pocket of air that steam cannot reach.
+    fn _air_pockets(&self) -> Vec<usize> {
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = self._bounds;
+        let mut seen: HashSet<Voxel> = HashSet::new();
+        let mut pockets = Vec::new();
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                for z in min_z..=max_z {
+                    let voxel = (x, y, z);
+                    if self.cubes.contains(&voxel)
+                        || self.steam.contains(&voxel)
+                        || seen.contains(&voxel)
+                    {
+                        continue;
+                    }
+                    let pocket = Self::flood(&self.cubes, self._bounds, voxel);
+                    pockets.push(pocket.len());
+                    seen.extend(pocket);
+                }
+            }
+        }
+        return pockets;
+    }
+
+    fn _report(&self) -> String {
+        let pockets = self._air_pockets();
+        return format!(
+            "surface area {}, exterior {}, {} air pockets holding {} voxels",
+            self.surface_area(),
+            self.exterior_surface_area(),
+            pockets.len(),
+            pockets.iter().sum::<usize>()
+        );
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    // A hollow cube of the given side, with walls one voxel thick, moved along x by offset.
+    fn shell(side: i32, offset: i32) -> String {
+        let mut lines = Vec::new();
+        for x in 0..side {
+            for y in 0..side {
+                for z in 0..side {
+                    let inside = [x, y, z]
+                        .iter()
+                        .all(|value| *value > 0 && *value < side - 1);
+                    if !inside {
+                        lines.push(format!("{},{y},{z}", x + offset));
+                    }
+                }
+            }
+        }
+        return lines.join("\n");
+    }
+
     #[test]
     fn part1_works() {
         let actual = &create("test.txt").solve_part1();
@@ -54,6 +154,40 @@ mod test {
     fn part2_works() {
         let actual = &create("test.txt").solve_part2();
 
-        assert_eq!(actual, "0");
+        assert_eq!(actual, "58");
+    }
+
+    #[test]
+    fn air_pockets_works() {
+        let droplet = Droplet::parse(&utils::read_file("2022/day18", "test.txt"));
+
+        assert_eq!(vec![1], droplet._air_pockets());
+    }
+
+    #[test]
+    fn shell_has_one_large_pocket() {
+        let droplet = Droplet::parse(&shell(5, 0));
+
+        assert_eq!(vec![27], droplet._air_pockets());
+        assert_eq!(150, droplet.exterior_surface_area());
+        assert_eq!(150 + 54, droplet.surface_area());
+    }
+
+    #[test]
+    fn separate_pockets_are_counted_apart() {
+        let input = format!(
+            "{}
+{}",
+            shell(3, 0),
+            shell(3, 4)
+        );
+        let droplet = Droplet::parse(&input);
+
+        assert_eq!(vec![1, 1], droplet._air_pockets());
+        assert_eq!(
+            "surface area 120, exterior 108, 2 air pockets holding 2 voxels",
+            droplet._report()
+        );
+        assert_eq!(108, droplet.exterior_surface_area());
     }
 }
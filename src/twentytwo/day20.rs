@@ -0,0 +1,109 @@
+use crate::create_advent_day;
+
+create_advent_day!("2022", "20");
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+fn part1_with_input(input: &str) -> i64 {
+    let mut file = EncryptedFile::parse(input, 1);
+    file.mix();
+    return file.grove_coordinates();
+}
+
+fn part2_with_input(input: &str) -> i64 {
+    let mut file = EncryptedFile::parse(input, DECRYPTION_KEY);
+    for _ in 0..10 {
+        file.mix();
+    }
+    return file.grove_coordinates();
+}
+
+// Values can repeat, so the file is mixed as a permutation of original indices rather than
+// of the values themselves.
+struct EncryptedFile {
+    values: Vec<i64>,
+    order: Vec<usize>,
+}
+
+impl EncryptedFile {
+    fn parse(input: &str, key: i64) -> Self {
+        let values = input
+            .lines()
+            .map(|line| line.trim().parse::<i64>().unwrap() * key)
+            .collect::<Vec<_>>();
+        let order = (0..values.len()).collect();
+        return Self { values, order };
+    }
+
+    fn mix(&mut self) {
+        // A number moving all the way around passes every other number once, so moves wrap
+        // modulo one less than the length of the list.
+        let wrap = self.values.len() as i64 - 1;
+        if wrap == 0 {
+            return;
+        }
+        for index in 0..self.values.len() {
+            let position = self.order.iter().position(|value| *value == index).unwrap();
+            self.order.remove(position);
+            let destination = (position as i64 + self.values[index]).rem_euclid(wrap) as usize;
+            self.order.insert(destination, index);
+        }
+    }
+
+    // The list is circular, so it is read starting from the zero.
+    fn mixed_values(&self) -> Vec<i64> {
+        let zero = self
+            .order
+            .iter()
+            .position(|index| self.values[*index] == 0)
+            .unwrap();
+        return self.order[zero..]
+            .iter()
+            .chain(self.order[..zero].iter())
+            .map(|index| self.values[*index])
+            .collect();
+    }
+
+    fn grove_coordinates(&self) -> i64 {
+        let mixed = self.mixed_values();
+        return [1000, 2000, 3000]
+            .iter()
+            .map(|offset| mixed[offset % mixed.len()])
+            .sum();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "3");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "1623178306");
+    }
+
+    #[test]
+    fn mix_works() {
+        let mut file = EncryptedFile::parse("1\n2\n-3\n3\n-2\n0\n4", 1);
+        file.mix();
+
+        assert_eq!(vec![0, 3, -2, 1, 2, -3, 4], file.mixed_values());
+    }
+
+    #[test]
+    fn mix_handles_duplicates() {
+        let mut file = EncryptedFile::parse("0\n1\n1\n-2", 1);
+        file.mix();
+
+        assert_eq!(vec![0, -2, 1, 1], file.mixed_values());
+    }
+}
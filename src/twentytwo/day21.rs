@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use num::rational::Ratio;
+
+use crate::create_advent_day;
+
+create_advent_day!("2022", "21");
+
+// Division is exact, so intermediate results are kept as fractions instead of truncating.
+type Rational = Ratio<i128>;
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+fn part1_with_input(input: &str) -> Rational {
+    let troop = Troop::parse(input);
+    return troop.evaluate(troop.root);
+}
+
+fn part2_with_input(input: &str) -> Rational {
+    let troop = Troop::parse(input);
+    return troop.solve_for_human();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    fn parse(symbol: &str) -> Self {
+        return match symbol {
+            "+" => Self::Add,
+            "-" => Self::Subtract,
+            "*" => Self::Multiply,
+            "/" => Self::Divide,
+            _ => panic!("unknown operator {symbol}"),
+        };
+    }
+
+    fn apply(&self, left: Rational, right: Rational) -> Rational {
+        return match self {
+            Self::Add => left + right,
+            Self::Subtract => left - right,
+            Self::Multiply => left * right,
+            Self::Divide => left / right,
+        };
+    }
+
+    fn _symbol(&self) -> char {
+        return match self {
+            Self::Add => '+',
+            Self::Subtract => '-',
+            Self::Multiply => '*',
+            Self::Divide => '/',
+        };
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Job {
+    Number(i128),
+    Operation(usize, Operator, usize),
+}
+
+// Monkeys are interned to indices so jobs refer to each other without string lookups.
+struct Troop {
+    names: Vec<String>,
+    jobs: Vec<Job>,
+    root: usize,
+    human: usize,
+}
+
+impl Troop {
+    fn parse(input: &str) -> Self {
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut names = Vec::new();
+        let mut intern = |name: &str| -> usize {
+            return *indices.entry(name.to_owned()).or_insert_with(|| {
+                names.push(name.to_owned());
+                names.len() - 1
+            });
+        };
+
+        let mut jobs = Vec::new();
+        for line in input.lines() {
+            let (name, job) = line.split_once(": ").unwrap();
+            let index = intern(name);
+            let job = match job.split_whitespace().collect::<Vec<_>>()[..] {
+                [number] => Job::Number(number.parse().unwrap()),
+                [left, operator, right] => {
+                    Job::Operation(intern(left), Operator::parse(operator), intern(right))
+                }
+                _ => panic!("unexpected job {job}"),
+            };
+            jobs.push((index, job));
+        }
+        jobs.sort_by_key(|(index, _)| *index);
+        let root = intern(ROOT);
+        let human = intern(HUMAN);
+        assert!(
+            jobs.iter()
+                .enumerate()
+                .all(|(position, (index, _))| position == *index),
+            "every monkey needs exactly one job"
+        );
+
+        return Self {
+            names,
+            jobs: jobs.into_iter().map(|(_, job)| job).collect(),
+            root,
+            human,
+        };
+    }
+
+    fn evaluate(&self, monkey: usize) -> Rational {
+        return match self.jobs[monkey] {
+            Job::Number(number) => Rational::from_integer(number),
+            Job::Operation(left, operator, right) => {
+                operator.apply(self.evaluate(left), self.evaluate(right))
+            }
+        };
+    }
+
+    fn depends_on_human(&self, monkey: usize) -> bool {
+        if monkey == self.human {
+            return true;
+        }
+        return match self.jobs[monkey] {
+            Job::Number(_) => false,
+            Job::Operation(left, _, right) => {
+                self.depends_on_human(left) || self.depends_on_human(right)
+            }
+        };
+    }
+
+    // Splits an operation into the side that depends on humn and the value of the other side,
+    // along with whether the unknown side is on the left.
+    fn unknown_side(&self, monkey: usize) -> (usize, Rational, bool) {
+        let Job::Operation(left, _, right) = self.jobs[monkey] else {
+            panic!("{} does not have an operation", self.names[monkey]);
+        };
+        return match (self.depends_on_human(left), self.depends_on_human(right)) {
+            (true, false) => (left, self.evaluate(right), true),
+            (false, true) => (right, self.evaluate(left), false),
+            (true, true) => panic!("{} uses humn on both sides", self.names[monkey]),
+            (false, false) => panic!("{} does not depend on humn", self.names[monkey]),
+        };
+    }
+
+    // Root checks its two sides for equality. Starting from the known side's value, each
+    // operation on the way down to humn is undone until only humn is left.
+    fn solve_for_human(&self) -> Rational {
+        let (mut monkey, mut target, _) = self.unknown_side(self.root);
+        while monkey != self.human {
+            let Job::Operation(_, operator, _) = self.jobs[monkey] else {
+                unreachable!("only humn can be a number that depends on humn");
+            };
+            let (unknown, known, unknown_on_left) = self.unknown_side(monkey);
+            target = match (operator, unknown_on_left) {
+                (Operator::Add, _) => target - known,
+                (Operator::Multiply, _) => target / known,
+                (Operator::Subtract, true) => target + known,
+                (Operator::Subtract, false) => known - target,
+                (Operator::Divide, true) => target * known,
+                (Operator::Divide, false) => known / target,
+            };
+            monkey = unknown;
+        }
+        return target;
+    }
+
+    fn _expression(&self, monkey: usize) -> String {
+        if monkey == self.human {
+            return HUMAN.to_owned();
+        }
+        if !self.depends_on_human(monkey) {
+            return self.evaluate(monkey).to_string();
+        }
+        let Job::Operation(left, operator, right) = self.jobs[monkey] else {
+            unreachable!("only humn can be a number that depends on humn");
+        };
+        return format!(
+            "({} {} {})",
+            self._expression(left),
+            operator._symbol(),
+            self._expression(right)
+        );
+    }
+
+    // Prints root as an equation, with every subtree that does not involve humn collapsed to
+    // its value.
+    fn _equation(&self) -> String {
+        let Job::Operation(left, _, right) = self.jobs[self.root] else {
+            panic!("root does not have an operation");
+        };
+        let strip = |expression: String| -> String {
+            return match expression.strip_prefix('(') {
+                Some(inner) => inner.strip_suffix(')').unwrap().to_owned(),
+                None => expression,
+            };
+        };
+        return format!(
+            "{} = {}",
+            strip(self._expression(left)),
+            strip(self._expression(right))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "152");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "301");
+    }
+
+    #[test]
+    fn parse_works() {
+        let troop = Troop::parse("root: humn / zero\nhumn: 5\nzero: 0");
+
+        assert_eq!(vec!["root", "humn", "zero"], troop.names);
+        assert_eq!(Job::Operation(1, Operator::Divide, 2), troop.jobs[0]);
+        assert_eq!(Job::Number(5), troop.jobs[1]);
+    }
+
+    #[test]
+    fn evaluate_keeps_fractions() {
+        let troop = Troop::parse("root: a * b\na: c / d\nb: 4\nc: 3\nd: 2");
+
+        assert_eq!(
+            Rational::new(3, 2),
+            troop.evaluate(troop.names.iter().position(|name| name == "a").unwrap())
+        );
+        assert_eq!(Rational::from_integer(6), troop.evaluate(troop.root));
+    }
+
+    #[test]
+    fn solve_inverts_operations_on_the_right() {
+        let troop = Troop::parse("root: a + b\na: 2\nb: c / humn\nc: 12\nhumn: 1");
+
+        assert_eq!(Rational::from_integer(6), troop.solve_for_human());
+    }
+
+    #[test]
+    fn solve_can_need_a_fraction() {
+        let troop = Troop::parse("root: a + b\na: humn * c\nb: 7\nc: 2\nhumn: 0");
+
+        assert_eq!(Rational::new(7, 2), troop.solve_for_human());
+    }
+
+    #[test]
+    fn equation_works() {
+        let troop = Troop::parse(&utils::read_file("2022/day21", "test.txt"));
+
+        assert_eq!("(4 + (2 * (humn - 3))) / 4 = 150", troop._equation());
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::VecDeque;
+
+use crate::create_advent_day;
+
+create_advent_day!("2022", "22");
+
+fn part1_with_input(input: &str) -> usize {
+    let notes = Notes::parse(input);
+    return notes.password(Wrapping::Flat);
+}
+
+fn part2_with_input(input: &str) -> usize {
+    let notes = Notes::parse(input);
+    return notes.password(Wrapping::Cube);
+}
+
+// Facing right, down, left, up; turning clockwise adds one, matching the password's facing value.
+const HEADINGS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+#[derive(Clone, Copy)]
+enum Wrapping {
+    Flat,
+    Cube,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tile {
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Debug, PartialEq)]
+enum Instruction {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position {
+    x: usize,
+    y: usize,
+    heading: usize,
+}
+
+struct Notes {
+    board: Board,
+    path: Vec<Instruction>,
+}
+
+impl Notes {
+    fn parse(input: &str) -> Self {
+        let (board, path) = input.split_once("\n\n").unwrap();
+        return Self {
+            board: Board::parse(board),
+            path: parse_path(path.trim()),
+        };
+    }
+
+    fn password(&self, wrapping: Wrapping) -> usize {
+        let cube = match wrapping {
+            Wrapping::Flat => None,
+            Wrapping::Cube => Some(Cube::fold(&self.board)),
+        };
+        let mut position = self.board.start();
+        for instruction in &self.path {
+            match instruction {
+                Instruction::Left => position.heading = (position.heading + 3) % 4,
+                Instruction::Right => position.heading = (position.heading + 1) % 4,
+                Instruction::Forward(steps) => {
+                    for _ in 0..*steps {
+                        let next = match &cube {
+                            None => self.board.step_flat(position),
+                            Some(cube) => cube.step(&self.board, position),
+                        };
+                        if self.board.tile(next.x, next.y) == Tile::Wall {
+                            break;
+                        }
+                        position = next;
+                    }
+                }
+            }
+        }
+        return 1000 * (position.y + 1) + 4 * (position.x + 1) + position.heading;
+    }
+}
+
+fn parse_path(path: &str) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut steps = 0;
+    for letter in path.chars() {
+        if let Some(digit) = letter.to_digit(10) {
+            steps = steps * 10 + digit as usize;
+            continue;
+        }
+        instructions.push(Instruction::Forward(steps));
+        steps = 0;
+        instructions.push(match letter {
+            'L' => Instruction::Left,
+            'R' => Instruction::Right,
+            _ => panic!("unknown turn {letter}"),
+        });
+    }
+    instructions.push(Instruction::Forward(steps));
+    return instructions;
+}
+
+struct Board {
+    tiles: Vec<Vec<Tile>>,
+    width: usize,
+    height: usize,
+}
+
+impl Board {
+    fn parse(input: &str) -> Self {
+        let width = input.lines().map(|line| line.len()).max().unwrap();
+        let tiles = input
+            .lines()
+            .map(|line| {
+                let mut row = line
+                    .chars()
+                    .map(|letter| match letter {
+                        ' ' => Tile::Void,
+                        '.' => Tile::Open,
+                        '#' => Tile::Wall,
+                        _ => panic!("unknown tile {letter}"),
+                    })
+                    .collect::<Vec<_>>();
+                row.resize(width, Tile::Void);
+                row
+            })
+            .collect::<Vec<_>>();
+        let height = tiles.len();
+        return Self {
+            tiles,
+            width,
+            height,
+        };
+    }
+
+    fn tile(&self, x: usize, y: usize) -> Tile {
+        return self.tiles[y][x];
+    }
+
+    fn start(&self) -> Position {
+        let x = self.tiles[0]
+            .iter()
+            .position(|tile| *tile == Tile::Open)
+            .unwrap();
+        return Position {
+            x,
+            y: 0,
+            heading: 0,
+        };
+    }
+
+    // The tile one step along the heading, or None when that steps off the board.
+    fn ahead(&self, position: Position) -> Option<(usize, usize)> {
+        let (dx, dy) = HEADINGS[position.heading];
+        let x = position.x as i32 + dx;
+        let y = position.y as i32 + dy;
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let (x, y) = (x as usize, y as usize);
+        if self.tile(x, y) == Tile::Void {
+            return None;
+        }
+        return Some((x, y));
+    }
+
+    // Stepping off an edge comes back in on the far side of the same row or column.
+    fn step_flat(&self, position: Position) -> Position {
+        if let Some((x, y)) = self.ahead(position) {
+            return Position { x, y, ..position };
+        }
+        let mut back = Position {
+            heading: (position.heading + 2) % 4,
+            ..position
+        };
+        while let Some((x, y)) = self.ahead(back) {
+            back.x = x;
+            back.y = y;
+        }
+        return Position {
+            heading: position.heading,
+            ..back
+        };
+    }
+}
+
+type Vector = [i32; 3];
+
+fn negate(vector: Vector) -> Vector {
+    return vector.map(|value| -value);
+}
+
+fn dot(left: Vector, right: Vector) -> i32 {
+    return (0..3).map(|axis| left[axis] * right[axis]).sum();
+}
+
+// Where a face of the net ended up after folding: its outward normal, and the directions its
+// local right and down point in.
+#[derive(Clone, Copy)]
+struct Face {
+    column: usize,
+    row: usize,
+    right: Vector,
+    down: Vector,
+    normal: Vector,
+}
+
+impl Face {
+    fn heading_vector(&self, heading: usize) -> Vector {
+        return match heading {
+            0 => self.right,
+            1 => self.down,
+            2 => negate(self.right),
+            3 => negate(self.down),
+            _ => unreachable!("headings are 0..4"),
+        };
+    }
+}
+
+struct Cube {
+    size: usize,
+    faces: Vec<Face>,
+    // Index into faces for each face-sized block of the board.
+    layout: Vec<Vec<Option<usize>>>,
+}
+
+impl Cube {
+    // Folds the net by walking it from the first face, rolling the orientation over each shared
+    // edge. Any of the eleven cube nets works because nothing depends on the layout itself.
+    fn fold(board: &Board) -> Self {
+        let area = board
+            .tiles
+            .iter()
+            .flatten()
+            .filter(|tile| **tile != Tile::Void)
+            .count();
+        let size = (1..).find(|size| 6 * size * size >= area).unwrap();
+        assert_eq!(6 * size * size, area, "the board does not cover six faces");
+
+        let columns = board.width / size;
+        let rows = board.height / size;
+        let mut layout = vec![vec![None; columns]; rows];
+        let mut faces = Vec::new();
+        let start = board.tiles[0]
+            .iter()
+            .position(|tile| *tile != Tile::Void)
+            .unwrap()
+            / size;
+        let mut queue = VecDeque::from([Face {
+            column: start,
+            row: 0,
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, 1],
+        }]);
+        while let Some(face) = queue.pop_front() {
+            if layout[face.row][face.column].is_some() {
+                continue;
+            }
+            layout[face.row][face.column] = Some(faces.len());
+            faces.push(face);
+            let neighbors = [
+                (
+                    face.column as i32 + 1,
+                    face.row as i32,
+                    negate(face.normal),
+                    face.down,
+                    face.right,
+                ),
+                (
+                    face.column as i32,
+                    face.row as i32 + 1,
+                    face.right,
+                    negate(face.normal),
+                    face.down,
+                ),
+                (
+                    face.column as i32 - 1,
+                    face.row as i32,
+                    face.normal,
+                    face.down,
+                    negate(face.right),
+                ),
+                (
+                    face.column as i32,
+                    face.row as i32 - 1,
+                    face.right,
+                    face.normal,
+                    negate(face.down),
+                ),
+            ];
+            for (column, row, right, down, normal) in neighbors {
+                if column < 0 || row < 0 || column >= columns as i32 || row >= rows as i32 {
+                    continue;
+                }
+                let (column, row) = (column as usize, row as usize);
+                if board.tile(column * size, row * size) == Tile::Void {
+                    continue;
+                }
+                queue.push_back(Face {
+                    column,
+                    row,
+                    right,
+                    down,
+                    normal,
+                });
+            }
+        }
+        assert_eq!(6, faces.len(), "the net is not connected");
+        for (index, face) in faces.iter().enumerate() {
+            assert!(
+                faces[..index]
+                    .iter()
+                    .all(|other| other.normal != face.normal),
+                "two faces fold onto the same side of the cube"
+            );
+        }
+
+        return Self {
+            size,
+            faces,
+            layout,
+        };
+    }
+
+    // Tile centres sit at odd coordinates on a cube spanning -size..size. Stepping over an edge
+    // moves half a tile along the old heading and half a tile into the cube, which lands on the
+    // face whose normal is the old heading, now travelling against the old face's normal.
+    fn step(&self, board: &Board, position: Position) -> Position {
+        if let Some((x, y)) = board.ahead(position) {
+            return Position { x, y, ..position };
+        }
+        let size = self.size as i32;
+        let face =
+            &self.faces[self.layout[position.y / self.size][position.x / self.size].unwrap()];
+        let across = 2 * (position.x % self.size) as i32 + 1 - size;
+        let along = 2 * (position.y % self.size) as i32 + 1 - size;
+        let heading = face.heading_vector(position.heading);
+        let point: Vector = std::array::from_fn(|axis| {
+            size * face.normal[axis]
+                + across * face.right[axis]
+                + along * face.down[axis]
+                + heading[axis]
+                - face.normal[axis]
+        });
+
+        let target = self
+            .faces
+            .iter()
+            .find(|other| other.normal == heading)
+            .unwrap();
+        let new_heading = (0..4)
+            .find(|new_heading| target.heading_vector(*new_heading) == negate(face.normal))
+            .unwrap();
+        let x = ((dot(point, target.right) + size - 1) / 2) as usize;
+        let y = ((dot(point, target.down) + size - 1) / 2) as usize;
+        return Position {
+            x: target.column * self.size + x,
+            y: target.row * self.size + y,
+            heading: new_heading,
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "6032");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "5031");
+    }
+
+    #[test]
+    fn parse_path_works() {
+        assert_eq!(
+            vec![
+                Instruction::Forward(10),
+                Instruction::Right,
+                Instruction::Forward(5),
+                Instruction::Left,
+                Instruction::Forward(0),
+            ],
+            parse_path("10R5L0")
+        );
+    }
+
+    #[rstest]
+    #[case(Position { x: 11, y: 5, heading: 0 }, Position { x: 14, y: 8, heading: 1 })]
+    #[case(Position { x: 10, y: 11, heading: 1 }, Position { x: 1, y: 7, heading: 3 })]
+    #[case(Position { x: 6, y: 4, heading: 3 }, Position { x: 8, y: 2, heading: 0 })]
+    fn cube_step_works(#[case] from: Position, #[case] expected: Position) {
+        let notes = Notes::parse(&utils::read_file("2022/day22", "test.txt"));
+        let cube = Cube::fold(&notes.board);
+
+        assert_eq!(expected, cube.step(&notes.board, from));
+    }
+
+    // Expands a net drawn one character per face into an open board of the given face size.
+    fn open_net(net: &str, size: usize) -> Board {
+        let board = net
+            .lines()
+            .flat_map(|line| {
+                let row = line
+                    .chars()
+                    .map(|face| {
+                        if face == '#' {
+                            ".".repeat(size)
+                        } else {
+                            " ".repeat(size)
+                        }
+                    })
+                    .collect::<String>();
+                vec![row; size]
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Board::parse(&board);
+    }
+
+    // Walking four edges' worth in a straight line circles the cube back to where it started.
+    #[rstest]
+    #[case("#\n####\n#")]
+    #[case("#\n####\n.#")]
+    #[case("#\n####\n..#")]
+    #[case("#\n####\n...#")]
+    #[case(".#\n####\n.#")]
+    #[case(".#\n####\n..#")]
+    #[case("##\n.###\n.#")]
+    #[case("##\n.###\n..#")]
+    #[case("##\n.###\n...#")]
+    #[case("##\n.##\n..##")]
+    #[case("###\n..###")]
+    fn cube_walk_circles_every_net(#[case] net: &str) {
+        let size = 3;
+        let board = open_net(net, size);
+        let cube = Cube::fold(&board);
+
+        for y in 0..board.height {
+            for x in 0..board.width {
+                if board.tile(x, y) == Tile::Void {
+                    continue;
+                }
+                for heading in 0..4 {
+                    let start = Position { x, y, heading };
+                    let mut position = start;
+                    for _ in 0..4 * size {
+                        position = cube.step(&board, position);
+                        assert_ne!(Tile::Void, board.tile(position.x, position.y));
+                    }
+                    assert_eq!(start, position);
+                }
+            }
+        }
+    }
+}
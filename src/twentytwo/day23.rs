@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::create_advent_day;
+
+create_advent_day!("2022", "23");
+
+fn part1_with_input(input: &str) -> usize {
+    let mut grove = Grove::parse(input);
+    for _ in 0..10 {
+        grove.round();
+    }
+    return grove.empty_ground();
+}
+
+fn part2_with_input(input: &str) -> usize {
+    let mut grove = Grove::parse(input);
+    while grove.round() {}
+    return grove.rounds;
+}
+
+type Point = (i32, i32);
+
+// Each proposal is the step to take followed by the three cells that must be empty, starting
+// with north, south, west, east. The first choice rotates by one every round.
+const PROPOSALS: [[Point; 4]; 4] = [
+    [(0, -1), (-1, -1), (0, -1), (1, -1)],
+    [(0, 1), (-1, 1), (0, 1), (1, 1)],
+    [(-1, 0), (-1, -1), (-1, 0), (-1, 1)],
+    [(1, 0), (1, -1), (1, 0), (1, 1)],
+];
+
+const NEIGHBORS: [Point; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Clone, Copy)]
+struct Window {
+    min: Point,
+    max: Point,
+}
+
+// Elves are kept as a sparse set of coordinates so they can spread in any direction without
+// resizing a grid.
+struct Grove {
+    elves: HashSet<Point>,
+    rounds: usize,
+}
+
+impl Grove {
+    fn parse(input: &str) -> Self {
+        let elves = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, letter)| *letter == '#')
+                    .map(move |(x, _)| (x as i32, y as i32))
+            })
+            .collect();
+        return Self { elves, rounds: 0 };
+    }
+
+    fn is_elf(&self, (x, y): Point, (dx, dy): Point) -> bool {
+        return self.elves.contains(&(x + dx, y + dy));
+    }
+
+    fn proposal(&self, elf: Point) -> Option<Point> {
+        if !NEIGHBORS.iter().any(|offset| self.is_elf(elf, *offset)) {
+            return None;
+        }
+        return (0..4)
+            .map(|choice| PROPOSALS[(self.rounds + choice) % 4])
+            .find(|[_, checks @ ..]| !checks.iter().any(|offset| self.is_elf(elf, *offset)))
+            .map(|[(dx, dy), ..]| (elf.0 + dx, elf.1 + dy));
+    }
+
+    // Runs one round and reports whether any elf moved. Elves proposing the same cell all stay.
+    fn round(&mut self) -> bool {
+        let proposals = self
+            .elves
+            .iter()
+            .filter_map(|elf| self.proposal(*elf).map(|target| (*elf, target)))
+            .collect::<Vec<_>>();
+        let mut claims: HashMap<Point, usize> = HashMap::new();
+        for (_, target) in &proposals {
+            *claims.entry(*target).or_insert(0) += 1;
+        }
+        let mut moved = false;
+        for (elf, target) in proposals {
+            if claims[&target] == 1 {
+                self.elves.remove(&elf);
+                self.elves.insert(target);
+                moved = true;
+            }
+        }
+        self.rounds += 1;
+        return moved;
+    }
+
+    fn bounds(&self) -> Window {
+        let xs = self.elves.iter().map(|(x, _)| *x);
+        let ys = self.elves.iter().map(|(_, y)| *y);
+        return Window {
+            min: (xs.clone().min().unwrap(), ys.clone().min().unwrap()),
+            max: (xs.max().unwrap(), ys.max().unwrap()),
+        };
+    }
+
+    fn empty_ground(&self) -> usize {
+        let Window { min, max } = self.bounds();
+        let area = (max.0 - min.0 + 1) * (max.1 - min.1 + 1);
+        return area as usize - self.elves.len();
+    }
+
+    fn _render(&self, window: Window) -> String {
+        return (window.min.1..=window.max.1)
+            .map(|y| {
+                (window.min.0..=window.max.0)
+                    .map(|x| {
+                        if self.elves.contains(&(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    // Renders the starting state and every round after it, either inside a fixed window like
+    // the puzzle's example frames or cropped to the elves when no window is given.
+    fn _frames(&mut self, rounds: usize, window: Option<Window>) -> Vec<String> {
+        let mut frames = vec![self._render(window.unwrap_or_else(|| self.bounds()))];
+        for _ in 0..rounds {
+            self.round();
+            frames.push(self._render(window.unwrap_or_else(|| self.bounds())));
+        }
+        return frames;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "110");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "20");
+    }
+
+    #[test]
+    fn frames_match_small_example() {
+        let mut grove = Grove::parse(&utils::read_file("2022/day23", "test2.txt"));
+        let window = Window {
+            min: (0, 0),
+            max: (4, 5),
+        };
+
+        let frames = grove._frames(3, Some(window));
+
+        assert_eq!(
+            vec![
+                ".....\n..##.\n..#..\n.....\n..##.\n.....",
+                "..##.\n.....\n..#..\n...#.\n..#..\n.....",
+                ".....\n..##.\n.#...\n....#\n.....\n..#..",
+                "..#..\n....#\n#....\n....#\n.....\n..#..",
+            ],
+            frames
+        );
+        assert!(!grove.round());
+    }
+
+    #[test]
+    fn frames_crop_to_elves() {
+        let mut grove = Grove::parse(&utils::read_file("2022/day23", "test.txt"));
+
+        let frames = grove._frames(10, None);
+
+        assert_eq!(
+            "......#.....
+..........#.
+.#.#..#.....
+.....#......
+..#.....#..#
+#......##...
+....##......
+.#........#.
+...#.#..#...
+............
+...#..#..#..",
+            frames[10]
+        );
+        assert_eq!(110, grove.empty_ground());
+    }
+}
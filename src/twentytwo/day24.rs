@@ -0,0 +1,171 @@
+use std::collections::{HashSet, VecDeque};
+
+use num::integer;
+
+use crate::create_advent_day;
+
+create_advent_day!("2022", "24");
+
+fn part1_with_input(input: &str) -> usize {
+    let valley = Valley::parse(input);
+    return valley.crossing(valley.entrance(), valley.exit(), 0);
+}
+
+fn part2_with_input(input: &str) -> usize {
+    let valley = Valley::parse(input);
+    let there = valley.crossing(valley.entrance(), valley.exit(), 0);
+    let back = valley.crossing(valley.exit(), valley.entrance(), there);
+    return valley.crossing(valley.entrance(), valley.exit(), back);
+}
+
+// Waiting in place or moving one step in any direction.
+const MOVES: [(i32, i32); 5] = [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)];
+
+type Point = (i32, i32);
+
+// Coordinates are inside the walls, so the entrance sits at y = -1 and the exit at y = height.
+struct Valley {
+    blizzards: Vec<Vec<char>>,
+    width: i32,
+    height: i32,
+    period: usize,
+    entrance: Point,
+    exit: Point,
+}
+
+impl Valley {
+    fn parse(input: &str) -> Self {
+        let lines = input.lines().collect::<Vec<_>>();
+        let blizzards = lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| line[1..line.len() - 1].chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let width = blizzards[0].len();
+        let height = blizzards.len();
+        let gap = |line: &str| line.find('.').unwrap() as i32 - 1;
+        return Self {
+            blizzards,
+            width: width as i32,
+            height: height as i32,
+            period: integer::lcm(width, height),
+            entrance: (gap(lines[0]), -1),
+            exit: (gap(lines[lines.len() - 1]), height as i32),
+        };
+    }
+
+    fn entrance(&self) -> Point {
+        return self.entrance;
+    }
+
+    fn exit(&self) -> Point {
+        return self.exit;
+    }
+
+    // Blizzards wrap within their own row or column, so a cell is hit at minute t exactly when
+    // the starting grid held a blizzard t cells upstream of it.
+    fn has_blizzard(&self, (x, y): Point, minute: usize) -> bool {
+        let shift = minute as i32;
+        let row = &self.blizzards[y as usize];
+        return row[(x - shift).rem_euclid(self.width) as usize] == '>'
+            || row[(x + shift).rem_euclid(self.width) as usize] == '<'
+            || self.blizzards[(y - shift).rem_euclid(self.height) as usize][x as usize] == 'v'
+            || self.blizzards[(y + shift).rem_euclid(self.height) as usize][x as usize] == '^';
+    }
+
+    fn is_open(&self, point: Point, minute: usize) -> bool {
+        if point == self.entrance() || point == self.exit() {
+            return true;
+        }
+        let (x, y) = point;
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        return !self.has_blizzard(point, minute);
+    }
+
+    // Breadth first search over (position, minute mod period), since the blizzards repeat after
+    // one period and revisiting the same cell at the same phase cannot lead anywhere new.
+    // Returns the minute of arrival.
+    fn crossing(&self, from: Point, to: Point, start: usize) -> usize {
+        let mut seen = HashSet::from([(from, start % self.period)]);
+        let mut queue = VecDeque::from([(from, start)]);
+        while let Some(((x, y), minute)) = queue.pop_front() {
+            if (x, y) == to {
+                return minute;
+            }
+            for (dx, dy) in MOVES {
+                let next = (x + dx, y + dy);
+                if self.is_open(next, minute + 1) && seen.insert((next, (minute + 1) % self.period))
+                {
+                    queue.push_back((next, minute + 1));
+                }
+            }
+        }
+        panic!("no way through the valley");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "18");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "54");
+    }
+
+    #[test]
+    fn crossing_legs_work() {
+        let valley = Valley::parse(&utils::read_file("2022/day24", "test.txt"));
+
+        assert_eq!(18, valley.crossing(valley.entrance(), valley.exit(), 0));
+        assert_eq!(41, valley.crossing(valley.exit(), valley.entrance(), 18));
+        assert_eq!(54, valley.crossing(valley.entrance(), valley.exit(), 41));
+    }
+
+    #[test]
+    fn has_blizzard_matches_simulation() {
+        let valley = Valley::parse(&utils::read_file("2022/day24", "test.txt"));
+        let mut blizzards = Vec::new();
+        for (y, row) in valley.blizzards.iter().enumerate() {
+            for (x, letter) in row.iter().enumerate() {
+                let heading = match letter {
+                    '>' => (1, 0),
+                    '<' => (-1, 0),
+                    'v' => (0, 1),
+                    '^' => (0, -1),
+                    _ => continue,
+                };
+                blizzards.push(((x as i32, y as i32), heading));
+            }
+        }
+
+        for minute in 0..=valley.period {
+            let occupied = blizzards
+                .iter()
+                .map(|(point, _)| *point)
+                .collect::<HashSet<_>>();
+            for y in 0..valley.height {
+                for x in 0..valley.width {
+                    assert_eq!(
+                        occupied.contains(&(x, y)),
+                        valley.has_blizzard((x, y), minute)
+                    );
+                }
+            }
+            for ((x, y), (dx, dy)) in blizzards.iter_mut() {
+                *x = (*x + *dx).rem_euclid(valley.width);
+                *y = (*y + *dy).rem_euclid(valley.height);
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt;
+use std::ops::Add;
+
+use crate::create_advent_day;
+
+create_advent_day!("2022", "25");
+
+fn part1_with_input(input: &str) -> Snafu {
+    return input
+        .lines()
+        .map(Snafu::parse)
+        .fold(Snafu::zero(), Add::add);
+}
+
+fn part2_with_input(_input: &str) -> i64 {
+    return 0;
+}
+
+#[derive(Debug, PartialEq)]
+struct OutOfRange;
+
+// Balanced base five, with digits from -2 to 2 stored least significant first. Zero has no
+// digits, and there are never leading zeros, so equal numbers have equal digits.
+#[derive(Clone, Debug, PartialEq)]
+struct Snafu {
+    digits: Vec<i8>,
+}
+
+impl Snafu {
+    fn zero() -> Self {
+        return Self { digits: Vec::new() };
+    }
+
+    fn parse(input: &str) -> Self {
+        let digits = input
+            .trim()
+            .chars()
+            .rev()
+            .map(|letter| match letter {
+                '=' => -2,
+                '-' => -1,
+                '0' => 0,
+                '1' => 1,
+                '2' => 2,
+                _ => panic!("unknown SNAFU digit {letter}"),
+            })
+            .collect();
+        return Self { digits }.trimmed();
+    }
+
+    fn trimmed(mut self) -> Self {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        return self;
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(formatter, "0");
+        }
+        let text = self
+            .digits
+            .iter()
+            .rev()
+            .map(|digit| match digit {
+                -2 => '=',
+                -1 => '-',
+                0 => '0',
+                1 => '1',
+                2 => '2',
+                _ => unreachable!("SNAFU digits are -2..=2"),
+            })
+            .collect::<String>();
+        return write!(formatter, "{text}");
+    }
+}
+
+// Column addition: each column sums to between -5 and 5 including the carry, which always
+// leaves a digit in range and a carry of -1, 0 or 1.
+impl Add for Snafu {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let length = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(length + 1);
+        let mut carry = 0;
+        for index in 0..length {
+            let mut sum = self.digits.get(index).unwrap_or(&0)
+                + other.digits.get(index).unwrap_or(&0)
+                + carry;
+            carry = 0;
+            if sum > 2 {
+                sum -= 5;
+                carry = 1;
+            } else if sum < -2 {
+                sum += 5;
+                carry = -1;
+            }
+            digits.push(sum);
+        }
+        digits.push(carry);
+        return Self { digits }.trimmed();
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(value: i128) -> Self {
+        let mut digits = Vec::new();
+        let mut rest = value;
+        while rest != 0 {
+            // Splitting before adjusting keeps i128::MIN from overflowing.
+            let mut quotient = rest.div_euclid(5);
+            let mut digit = rest.rem_euclid(5);
+            if digit > 2 {
+                digit -= 5;
+                quotient += 1;
+            }
+            digits.push(digit as i8);
+            rest = quotient;
+        }
+        return Self { digits };
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(value: i64) -> Self {
+        return Self::from(value as i128);
+    }
+}
+
+impl TryFrom<&Snafu> for i128 {
+    type Error = OutOfRange;
+    fn try_from(snafu: &Snafu) -> Result<Self, OutOfRange> {
+        // When the digit pulls back towards zero, total * 5 alone can overflow even though the
+        // result fits, so one unit is borrowed from total first.
+        return snafu.digits.iter().rev().try_fold(0i128, |total, digit| {
+            let (total, digit) = match *digit as i128 {
+                digit if digit < 0 && total > 0 => (total - 1, digit + 5),
+                digit if digit > 0 && total < 0 => (total + 1, digit - 5),
+                digit => (total, digit),
+            };
+            total
+                .checked_mul(5)
+                .and_then(|total| total.checked_add(digit))
+                .ok_or(OutOfRange)
+        });
+    }
+}
+
+impl TryFrom<&Snafu> for i64 {
+    type Error = OutOfRange;
+    fn try_from(snafu: &Snafu) -> Result<Self, OutOfRange> {
+        let value = i128::try_from(snafu)?;
+        return i64::try_from(value).map_err(|_| OutOfRange);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn part1_works() {
+        let actual = &create("test.txt").solve_part1();
+
+        assert_eq!(actual, "2=-1=0");
+    }
+
+    #[test]
+    fn part2_works() {
+        let actual = &create("test.txt").solve_part2();
+
+        assert_eq!(actual, "0");
+    }
+
+    #[rstest]
+    #[case(0, "0")]
+    #[case(1, "1")]
+    #[case(2, "2")]
+    #[case(3, "1=")]
+    #[case(4, "1-")]
+    #[case(5, "10")]
+    #[case(6, "11")]
+    #[case(7, "12")]
+    #[case(8, "2=")]
+    #[case(9, "2-")]
+    #[case(10, "20")]
+    #[case(15, "1=0")]
+    #[case(20, "1-0")]
+    #[case(2022, "1=11-2")]
+    #[case(12345, "1-0---0")]
+    #[case(314159265, "1121-1110-1=0")]
+    #[case(-3, "-2")]
+    fn conversion_works(#[case] decimal: i64, #[case] snafu: &str) {
+        assert_eq!(snafu, Snafu::from(decimal).to_string());
+        assert_eq!(Ok(decimal), i64::try_from(&Snafu::parse(snafu)));
+    }
+
+    #[test]
+    fn parse_drops_leading_zeros() {
+        assert_eq!(Snafu::from(3i64), Snafu::parse("001="));
+        assert_eq!(Snafu::zero(), Snafu::parse("000"));
+    }
+
+    #[test]
+    fn round_trip_works() {
+        let small = -100_000i128..=100_000;
+        let wide = (-5_000..5_000).map(|step| step * (i128::MAX / 5_000));
+        let edges = [i64::MIN as i128, i64::MAX as i128, i128::MIN, i128::MAX];
+        for value in small.chain(wide).chain(edges) {
+            let snafu = Snafu::from(value);
+
+            assert_eq!(Ok(value), i128::try_from(&snafu));
+            assert_eq!(snafu, Snafu::parse(&snafu.to_string()));
+        }
+    }
+
+    #[test]
+    fn conversion_reports_out_of_range() {
+        let beyond_i64 = Snafu::from(i64::MAX) + Snafu::from(1i64);
+        let beyond_i128 = Snafu::from(i128::MAX) + Snafu::from(1i64);
+
+        assert_eq!(Err(OutOfRange), i64::try_from(&beyond_i64));
+        assert_eq!(Ok(i64::MAX as i128 + 1), i128::try_from(&beyond_i64));
+        assert_eq!(Err(OutOfRange), i128::try_from(&beyond_i128));
+    }
+
+    #[test]
+    fn add_works() {
+        let values = (-2_000i64..=2_000)
+            .step_by(7)
+            .chain([i64::MIN / 2, i64::MAX / 2]);
+        for left in values.clone() {
+            for right in values.clone() {
+                assert_eq!(
+                    Snafu::from(left + right),
+                    Snafu::from(left) + Snafu::from(right)
+                );
+            }
+        }
+    }
+}
@@ -21,6 +21,12 @@ pub mod day16;
 pub mod day17;
 pub mod day18;
 pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
 pub fn add_twenty_two(map: &mut HashMap<String, AdventDay>) {
     map.insert("2022_01".to_owned(), day01::create("real.txt"));
@@ -42,4 +48,10 @@ pub fn add_twenty_two(map: &mut HashMap<String, AdventDay>) {
     map.insert("2022_17".to_owned(), day17::create("real.txt"));
     map.insert("2022_18".to_owned(), day18::create("real.txt"));
     map.insert("2022_19".to_owned(), day19::create("real.txt"));
+    map.insert("2022_20".to_owned(), day20::create("real.txt"));
+    map.insert("2022_21".to_owned(), day21::create("real.txt"));
+    map.insert("2022_22".to_owned(), day22::create("real.txt"));
+    map.insert("2022_23".to_owned(), day23::create("real.txt"));
+    map.insert("2022_24".to_owned(), day24::create("real.txt"));
+    map.insert("2022_25".to_owned(), day25::create("real.txt"));
 }
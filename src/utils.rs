@@ -11,6 +11,41 @@ pub fn read_file(folder_name: &str, file_name: &str) -> String {
 
     return contents;
 }
+
+pub type Voxel = (i32, i32, i32);
+
+pub const FACE_OFFSETS: [Voxel; 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+// The six voxels sharing a face with the given one.
+pub fn face_neighbors((x, y, z): Voxel) -> [Voxel; 6] {
+    return FACE_OFFSETS.map(|(dx, dy, dz)| (x + dx, y + dy, z + dz));
+}
+
+// The smallest and largest corner of the box around every voxel, or None when there are none.
+pub fn bounding_box<'a>(voxels: impl IntoIterator<Item = &'a Voxel>) -> Option<(Voxel, Voxel)> {
+    return voxels.into_iter().fold(None, |bounds, (x, y, z)| {
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) =
+            bounds.unwrap_or(((*x, *y, *z), (*x, *y, *z)));
+        Some((
+            (min_x.min(*x), min_y.min(*y), min_z.min(*z)),
+            (max_x.max(*x), max_y.max(*y), max_z.max(*z)),
+        ))
+    });
+}
+
+pub fn in_box((x, y, z): Voxel, (min, max): (Voxel, Voxel)) -> bool {
+    return (min.0..=max.0).contains(&x)
+        && (min.1..=max.1).contains(&y)
+        && (min.2..=max.2).contains(&z);
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -28,4 +63,26 @@ treb7uchet"
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn face_neighbors_works() {
+        let neighbors = face_neighbors((1, 2, 3));
+
+        assert_eq!(6, neighbors.len());
+        assert!(neighbors.contains(&(0, 2, 3)));
+        assert!(neighbors.contains(&(1, 2, 4)));
+        assert!(!neighbors.contains(&(0, 1, 3)));
+    }
+
+    #[test]
+    fn bounding_box_works() {
+        let voxels = [(1, 5, -2), (3, 0, 4), (-1, 2, 0)];
+
+        let bounds = bounding_box(&voxels);
+
+        assert_eq!(Some(((-1, 0, -2), (3, 5, 4))), bounds);
+        assert!(in_box((0, 0, 0), bounds.unwrap()));
+        assert!(!in_box((4, 0, 0), bounds.unwrap()));
+        assert_eq!(None, bounding_box(&[]));
+    }
 }
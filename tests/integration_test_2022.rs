@@ -235,11 +235,13 @@ mod test {
         assert_eq!(actual, "3090");
     }
 
+    // TODO: this was the example answer while part 2 was hardcoded, fill in the real one
+    #[ignore]
     #[test]
     fn day17_part2() {
         let actual = day17::create("real.txt").solve_part2();
 
-        assert_eq!(actual, "1514285714288");
+        assert_eq!(actual, "");
     }
 
     #[test]
@@ -249,12 +251,12 @@ mod test {
         assert_eq!(actual, "4314");
     }
 
-    // TODO: bring this back when i figure it out
+    // TODO: part 2 was a stub returning 0, fill in the real answer
     #[test]
     #[ignore]
     fn day18_part2() {
         let actual = day18::create("real.txt").solve_part2();
 
-        assert_eq!(actual, "0");
+        assert_eq!(actual, "");
     }
 }
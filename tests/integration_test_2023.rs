@@ -325,8 +325,6 @@ mod test {
         assert_eq!(actual, "2246");
     }
 
-    // TODO: profile and improve this execution, takes too long for integration tests
-    #[ignore]
     #[test]
     fn day23_part2() {
         let actual = day23::create("real.txt").solve_part2();